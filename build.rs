@@ -0,0 +1,71 @@
+//! Generates the multicodec table from `src/multicodec/table.csv`.
+//!
+//! The CSV is a verbatim copy of the official table, see
+//! `src/multicodec.rs` for its revision. Each row becomes one entry of the
+//! `build_multicodec_enum!` invocation in `$OUT_DIR/multicodec.rs`.
+
+use std::{env, fmt::Write, fs, path::Path};
+
+const TABLE: &str = "src/multicodec/table.csv";
+
+fn main() {
+	println!("cargo:rerun-if-changed={}", TABLE);
+	let csv = fs::read_to_string(TABLE).expect("multicodec table");
+
+	let mut rows = Vec::new();
+	for (number, line) in csv.lines().enumerate().skip(1) {
+		let fields: Vec<_> = line.split(',').map(str::trim).collect();
+		let [name, tag, code, status, description] = fields[..] else {
+			panic!("{}:{}: expected 5 fields", TABLE, number + 1);
+		};
+		let code = code
+			.strip_prefix("0x")
+			.and_then(|code| u64::from_str_radix(code, 16).ok())
+			.unwrap_or_else(|| panic!("{}:{}: invalid code", TABLE, number + 1));
+		rows.push((code, name, tag, status, description));
+	}
+	// `Multicodec::ALL` is ordered by code.
+	rows.sort_by_key(|row| row.0);
+
+	let mut output = String::from("build_multicodec_enum! {\n");
+	for (code, name, tag, status, description) in rows {
+		let mut doc = format!("`{}` ({:#x})", name, code);
+		if !description.is_empty() {
+			write!(doc, ": {}", description).unwrap();
+		}
+		writeln!(
+			output,
+			"\t{:?}, {}, {:?}, {}, {}, {:#x};",
+			doc,
+			identifier(name),
+			name,
+			identifier(tag),
+			identifier(status),
+			code
+		)
+		.unwrap();
+	}
+	output.push_str("}\n");
+
+	let out_dir = env::var_os("OUT_DIR").expect("OUT_DIR");
+	fs::write(Path::new(&out_dir).join("multicodec.rs"), output)
+		.expect("write multicodec table");
+}
+
+/// Turns a name into an UpperCamelCase identifier, e.g. `sha2-256` into
+/// `Sha2_256`. Digits that would run together are separated by `_`.
+fn identifier(name: &str) -> String {
+	let mut identifier = String::new();
+	for part in name.split(['-', '_']).filter(|part| !part.is_empty()) {
+		let mut chars = part.chars();
+		let first = chars.next().unwrap();
+		if identifier.ends_with(|c: char| c.is_ascii_digit())
+			&& first.is_ascii_digit()
+		{
+			identifier.push('_');
+		}
+		identifier.push(first.to_ascii_uppercase());
+		identifier.extend(chars);
+	}
+	identifier
+}
@@ -0,0 +1,168 @@
+use {
+	super::{
+		error::{async_io_error, Error},
+		header::CarHeader,
+		util::{ld_length, parse_node, section_buf},
+	},
+	crate::{cid::Cid, varint},
+	alloc::{string::ToString, vec::Vec},
+	embedded_io_async::{Read, ReadExactError},
+	futures_util::{stream, Stream},
+};
+
+/// Reads CAR files from an async reader.
+///
+/// This is the async counterpart of [`CarReader`](super::CarReader), it only
+/// needs an executor, no `std`.
+///
+/// Needs Rust 1.75, see the [module docs](super).
+#[derive(Debug)]
+pub struct AsyncCarReader<R> {
+	reader: R,
+	header: CarHeader,
+	buffer: Vec<u8>,
+}
+
+impl<R> AsyncCarReader<R>
+where
+	R: Read,
+{
+	/// Creates a new AsyncCarReader and parses the CarHeader
+	pub async fn new(mut reader: R) -> Result<Self, Error> {
+		let mut buffer = Vec::new();
+
+		match ld_read(&mut reader, &mut buffer).await? {
+			Some(buf) => {
+				let header = CarHeader::decode(buf)?;
+
+				Ok(AsyncCarReader {
+					reader,
+					header,
+					buffer,
+				})
+			}
+			None => Err(Error::Parsing(
+				"failed to parse uvarint for header".to_string(),
+			)),
+		}
+	}
+
+	/// Returns the header of this car file.
+	pub fn header(&self) -> &CarHeader {
+		&self.header
+	}
+
+	/// Returns the next IPLD Block in the buffer
+	pub async fn next_block(&mut self) -> Result<Option<(Cid, Vec<u8>)>, Error> {
+		match ld_read(&mut self.reader, &mut self.buffer).await? {
+			Some(buf) => parse_node(buf).map(Some),
+			None => Ok(None),
+		}
+	}
+
+	/// Returns a stream of the remaining blocks.
+	///
+	/// The stream ends after the first error.
+	pub fn stream(self) -> impl Stream<Item = Result<(Cid, Vec<u8>), Error>> {
+		stream::unfold(Some(self), |reader| async move {
+			let mut reader = reader?;
+			match reader.next_block().await {
+				Ok(Some(block)) => Some((Ok(block), Some(reader))),
+				Ok(None) => None,
+				Err(err) => Some((Err(err), None)),
+			}
+		})
+	}
+}
+
+/// Reads a length-delimited section into the buffer, see
+/// [`ld_read`](super::util::ld_read).
+async fn ld_read<'a, R: Read>(
+	reader: &mut R,
+	buf: &'a mut Vec<u8>,
+) -> Result<Option<&'a [u8]>, Error> {
+	let mut prefix = varint::encode::usize_buffer();
+	let mut len = 0;
+	let length = loop {
+		let n = reader
+			.read(&mut prefix[len..=len])
+			.await
+			.map_err(async_io_error)?;
+		if n == 0 {
+			return Ok(None);
+		}
+		len += 1;
+		if let Some((length, _)) = ld_length(&prefix[..len])? {
+			break length;
+		}
+	};
+
+	let section = section_buf(buf, length);
+	match reader.read_exact(section).await {
+		Ok(()) => Ok(Some(section)),
+		Err(ReadExactError::UnexpectedEof) => {
+			Err(Error::Parsing("unexpected end of file".to_string()))
+		}
+		Err(ReadExactError::Other(err)) => Err(async_io_error(err)),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::{
+			super::{async_writer::AsyncCarWriter, test_cid, *},
+			*,
+		},
+		alloc::vec,
+		futures::{executor::block_on, StreamExt},
+	};
+
+	#[test]
+	fn async_car_write_read() {
+		block_on(async {
+			let (cid_test, cid_foo) = (test_cid(b"test"), test_cid(b"foo"));
+			let header = CarHeader::new_v1(vec![cid_foo]);
+
+			let mut writer = AsyncCarWriter::new(header.clone(), Vec::new());
+			writer.write(cid_test, b"test").await.unwrap();
+			writer.write(cid_foo, b"foo").await.unwrap();
+			let buffer = writer.finish().await.unwrap();
+
+			// Both formats are the same.
+			let mut sync_buffer = Vec::new();
+			let mut sync_writer = CarWriter::new(header.clone(), &mut sync_buffer);
+			sync_writer.write(cid_test, b"test").unwrap();
+			sync_writer.write(cid_foo, b"foo").unwrap();
+			sync_writer.finish().unwrap();
+			assert_eq!(buffer, sync_buffer);
+
+			let mut reader = AsyncCarReader::new(buffer.as_slice()).await.unwrap();
+			assert_eq!(reader.header(), &header);
+			let block = reader.next_block().await.unwrap().unwrap();
+			assert_eq!(block, (cid_test, b"test".to_vec()));
+
+			let blocks: Vec<_> = reader.stream().collect().await;
+			assert_eq!(blocks.len(), 1);
+			assert_eq!(*blocks[0].as_ref().unwrap(), (cid_foo, b"foo".to_vec()));
+		});
+	}
+
+	#[test]
+	fn async_car_read_truncated() {
+		block_on(async {
+			let header = CarHeader::new_v1(vec![test_cid(b"foo")]);
+			let mut writer = AsyncCarWriter::new(header, Vec::new());
+			writer.write(test_cid(b"foo"), b"foo").await.unwrap();
+			let buffer = writer.finish().await.unwrap();
+
+			let truncated = &buffer[..buffer.len() - 1];
+			let reader = AsyncCarReader::new(truncated).await.unwrap();
+			let blocks: Vec<_> = reader.stream().collect().await;
+			assert_eq!(blocks.len(), 1);
+			assert!(matches!(blocks[0], Err(Error::Parsing(_))));
+
+			assert!(AsyncCarReader::new(&[][..]).await.is_err());
+		});
+	}
+}
@@ -0,0 +1,114 @@
+use {
+	super::{
+		error::{async_io_error, Error},
+		header::CarHeader,
+	},
+	crate::{cid::Cid, varint},
+	alloc::vec::Vec,
+	embedded_io_async::Write,
+};
+
+/// Writes CAR files to an async writer.
+///
+/// This is the async counterpart of [`CarWriter`](super::CarWriter), it only
+/// needs an executor, no `std`.
+///
+/// Needs Rust 1.75, see the [module docs](super).
+#[derive(Debug)]
+pub struct AsyncCarWriter<W> {
+	header: CarHeader,
+	writer: W,
+	cid_buffer: Vec<u8>,
+	is_header_written: bool,
+}
+
+impl<W> AsyncCarWriter<W>
+where
+	W: Write,
+{
+	/// Creates a new AsyncCarWriter, the header is written with the first
+	/// block.
+	pub fn new(header: CarHeader, writer: W) -> Self {
+		AsyncCarWriter {
+			header,
+			writer,
+			cid_buffer: Vec::new(),
+			is_header_written: false,
+		}
+	}
+
+	/// Forces the header to be written. Also called implicitly by `write`.
+	///
+	/// Returns the bytes written in this operation.
+	pub async fn write_header(&mut self) -> Result<usize, Error> {
+		let mut written = 0;
+
+		if !self.is_header_written {
+			// Write header bytes
+			let header_bytes = self.header.encode()?;
+			written += self.write_varint_usize(header_bytes.len()).await?;
+			self.write_all(&header_bytes).await?;
+			written += header_bytes.len();
+			self.is_header_written = true;
+		}
+
+		Ok(written)
+	}
+
+	/// Writes header and stream of data to writer in Car format.
+	///
+	/// Returns the bytes written in this operation.
+	pub async fn write<T>(&mut self, cid: Cid, data: T) -> Result<usize, Error>
+	where
+		T: AsRef<[u8]>,
+	{
+		let mut written = 0;
+		written += self.write_header().await?;
+
+		// Write the given block.
+		self.cid_buffer.clear();
+		cid.write_bytes(&mut self.cid_buffer).expect("vec write");
+
+		let data = data.as_ref();
+		let len = self.cid_buffer.len() + data.len();
+
+		written += self.write_varint_usize(len).await?;
+		self
+			.writer
+			.write_all(&self.cid_buffer)
+			.await
+			.map_err(async_io_error)?;
+		self.write_all(data).await?;
+		written += self.cid_buffer.len();
+		written += data.len();
+
+		Ok(written)
+	}
+
+	/// Finishes writing, including flushing and returns the writer.
+	pub async fn finish(mut self) -> Result<W, Error> {
+		self.flush().await?;
+		Ok(self.writer)
+	}
+
+	/// Flushes the underlying writer.
+	pub async fn flush(&mut self) -> Result<(), Error> {
+		self.writer.flush().await.map_err(async_io_error)
+	}
+
+	/// Consumes the [`AsyncCarWriter`] and returns the underlying writer.
+	pub fn into_inner(self) -> W {
+		self.writer
+	}
+
+	async fn write_all(&mut self, bytes: &[u8]) -> Result<(), Error> {
+		self.writer.write_all(bytes).await.map_err(async_io_error)
+	}
+
+	async fn write_varint_usize(&mut self, num: usize) -> Result<usize, Error> {
+		let mut buffer = varint::encode::usize_buffer();
+		let bytes = varint::encode::usize(num, &mut buffer);
+		self.write_all(bytes).await?;
+		Ok(bytes.len())
+	}
+}
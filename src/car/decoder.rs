@@ -0,0 +1,198 @@
+use {
+	super::{
+		error::Error,
+		header::CarHeader,
+		util::{ld_split, split_node, MAX_ALLOC},
+	},
+	crate::{cid::Cid, varint},
+	alloc::{string::ToString, vec::Vec},
+};
+
+/// An event of the [`CarDecoder`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum CarEvent<'a> {
+	/// The header was decoded, it's always the first event.
+	Header(&'a CarHeader),
+	/// A block was decoded.
+	Block(Cid, &'a [u8]),
+	/// The buffered data doesn't contain a whole section, more needs to be
+	/// pushed.
+	NeedMore,
+}
+
+/// Decodes CAR files from chunks of data, without doing any I/O.
+///
+/// The data is fed with [`CarDecoder::push`] as it arrives, then
+/// [`CarDecoder::next_event`] is called until it returns
+/// [`CarEvent::NeedMore`].
+///
+/// Every pushed chunk is copied into an internal buffer, which only gives up
+/// the decoded sections on the next push. So the events should be drained
+/// between pushes, and the buffer can't grow past the largest section that can
+/// be read, see [`CarDecoder::push`].
+#[derive(Debug, Default)]
+pub struct CarDecoder {
+	buffer: Vec<u8>,
+	position: usize,
+	header: Option<CarHeader>,
+}
+
+impl CarDecoder {
+	/// Creates a new CarDecoder that expects the CarHeader first
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns the header, once it's decoded.
+	pub fn header(&self) -> Option<&CarHeader> {
+		self.header.as_ref()
+	}
+
+	/// Appends a chunk of data.
+	///
+	/// Returns `Error::LdReadTooLarge` with nothing appended if the undecoded
+	/// data would grow past a length prefix and the largest section that can be
+	/// read.
+	pub fn push(&mut self, data: &[u8]) -> Result<(), Error> {
+		let len = self.buffer.len() - self.position + data.len();
+		if len > varint::encode::usize_buffer().len() + MAX_ALLOC {
+			return Err(Error::LdReadTooLarge(len));
+		}
+		// The decoded sections aren't borrowed anymore.
+		self.buffer.drain(..self.position);
+		self.position = 0;
+		self.buffer.extend_from_slice(data);
+		Ok(())
+	}
+
+	/// Decodes the next section of the buffered data.
+	pub fn next_event(&mut self) -> Result<CarEvent<'_>, Error> {
+		let (section, len) = match ld_split(&self.buffer[self.position..])? {
+			Some(split) => split,
+			None => return Ok(CarEvent::NeedMore),
+		};
+		// The section is only consumed once it's decoded, so that an error is
+		// returned again instead of decoding the data after it.
+		let event = match self.header {
+			None => CarEvent::Header(self.header.insert(CarHeader::decode(section)?)),
+			Some(_) => {
+				let (cid, data) = split_node(section)?;
+				CarEvent::Block(cid, data)
+			}
+		};
+		self.position += len;
+		Ok(event)
+	}
+
+	/// Checks that the data ended with a complete section.
+	pub fn finish(self) -> Result<(), Error> {
+		if self.header.is_none() {
+			return Err(Error::Parsing("missing header".to_string()));
+		}
+		if self.position != self.buffer.len() {
+			return Err(Error::Parsing("unexpected end of file".to_string()));
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::{
+			super::{test_cid, writer::CarWriter},
+			*,
+		},
+		alloc::vec,
+	};
+
+	fn car() -> (CarHeader, Vec<u8>) {
+		let header = CarHeader::new_v1(vec![test_cid(b"foo")]);
+		let mut buffer = Vec::new();
+		let mut writer = CarWriter::new(header.clone(), &mut buffer);
+		writer.write(test_cid(b"test"), b"test").unwrap();
+		writer.write(test_cid(b"foo"), b"foo").unwrap();
+		writer.finish().unwrap();
+		(header, buffer)
+	}
+
+	#[test]
+	fn car_decoder_chunks() {
+		let (header, buffer) = car();
+		for chunk_size in [1, 2, 7, buffer.len()] {
+			let mut decoder = CarDecoder::new();
+			let mut headers = 0;
+			let mut blocks = Vec::new();
+			for chunk in buffer.chunks(chunk_size) {
+				decoder.push(chunk).unwrap();
+				loop {
+					match decoder.next_event().unwrap() {
+						CarEvent::Header(decoded) => {
+							assert_eq!(decoded, &header);
+							headers += 1;
+						}
+						CarEvent::Block(cid, data) => blocks.push((cid, data.to_vec())),
+						CarEvent::NeedMore => break,
+					}
+				}
+			}
+			assert_eq!(headers, 1);
+			assert_eq!(decoder.header(), Some(&header));
+			assert_eq!(blocks, [
+				(test_cid(b"test"), b"test".to_vec()),
+				(test_cid(b"foo"), b"foo".to_vec())
+			]);
+			decoder.finish().unwrap();
+		}
+	}
+
+	#[test]
+	fn car_decoder_incomplete() {
+		let (_, buffer) = car();
+		let mut decoder = CarDecoder::new();
+		decoder.push(&buffer[..buffer.len() - 1]).unwrap();
+		while decoder.next_event().unwrap() != CarEvent::NeedMore {}
+		assert!(decoder.finish().is_err());
+
+		assert!(CarDecoder::new().finish().is_err());
+
+		// A section that is too large fails before it's buffered.
+		let mut decoder = CarDecoder::new();
+		decoder.push(&[0xff, 0xff, 0xff, 0x7f]).unwrap();
+		assert!(matches!(
+			decoder.next_event(),
+			Err(Error::LdReadTooLarge(_))
+		));
+
+		// Undecoded data is only buffered up to the largest section.
+		let mut decoder = CarDecoder::new();
+		let chunk = vec![0; MAX_ALLOC];
+		decoder.push(&chunk).unwrap();
+		assert!(matches!(
+			decoder.push(&chunk[..16]),
+			Err(Error::LdReadTooLarge(_))
+		));
+	}
+
+	#[test]
+	fn car_decoder_invalid_section() {
+		let (_, mut buffer) = car();
+		// The first byte of the CID of the first block.
+		let header_len = usize::from(buffer[0]) + 1;
+		buffer[header_len + 1] = 0xff;
+
+		let mut decoder = CarDecoder::new();
+		decoder.push(&buffer).unwrap();
+		assert!(matches!(decoder.next_event(), Ok(CarEvent::Header(_))));
+		// The error sticks, the following block isn't decoded.
+		for _ in 0..2 {
+			assert!(matches!(decoder.next_event(), Err(Error::Parsing(_))));
+		}
+		assert!(decoder.finish().is_err());
+
+		let mut decoder = CarDecoder::new();
+		decoder.push(&[0x01, 0xff]).unwrap();
+		assert!(decoder.next_event().is_err());
+		assert!(decoder.finish().is_err());
+	}
+}
@@ -0,0 +1,307 @@
+use {
+	super::{
+		error::Error,
+		header::CarHeader,
+		util::write_varint_usize,
+		writer::CarWriter,
+	},
+	crate::cid::Cid,
+	alloc::{format, vec::Vec},
+	core2::io::{Seek, SeekFrom, Write},
+};
+
+/// The fixed bytes at the start of every CARv2 file.
+const V2_PRAGMA: [u8; 11] = [
+	0x0a, 0xa1, 0x67, 0x76, 0x65, 0x72, 0x73, 0x69, 0x6f, 0x6e, 0x02,
+];
+
+/// The characteristics, data offset, data size and index offset.
+const V2_HEADER_LEN: usize = 40;
+
+#[derive(Debug)]
+enum Layout {
+	/// The placeholder header has to be replaced by one of the same size.
+	V1 { header_len: usize },
+	/// The CARv1 header is written at the end of the reserved space, the rest
+	/// is padding before the data offset.
+	V2 { reserved: usize },
+}
+
+/// Writes CAR files whose roots are only known after the blocks are written.
+///
+/// A DAG that is built bottom-up only knows its root at the end. Instead of
+/// buffering all the blocks, the header space is reserved up front and
+/// filled in by [`DeferredCarWriter::finish`], so the sink must be seekable.
+/// Plain streams need the roots before the first block, see [`CarWriter`].
+#[derive(Debug)]
+pub struct DeferredCarWriter<W> {
+	writer: CarWriter<W>,
+	start: u64,
+	layout: Layout,
+}
+
+impl<W> DeferredCarWriter<W>
+where
+	W: Write + Seek + Send + Unpin,
+{
+	/// Creates a CARv1 writer and writes the placeholder header.
+	///
+	/// CARv1 has no room for padding, so the final roots must encode to the
+	/// same size as the placeholder roots. Use CIDs of the same version,
+	/// codec and hash as the real ones, e.g. with a zeroed digest.
+	pub fn new_v1(placeholder: CarHeader, mut writer: W) -> Result<Self, Error> {
+		let start = position(&mut writer)?;
+		let mut writer = CarWriter::new(placeholder, writer);
+		let header_len = writer.write_header()?;
+		Ok(Self {
+			writer,
+			start,
+			layout: Layout::V1 { header_len },
+		})
+	}
+
+	/// Creates a CARv2 writer that reserves `reserved` bytes for the CARv1
+	/// header, including its length prefix.
+	///
+	/// The roots may encode to any size that fits, the CARv2 header is filled
+	/// in by [`DeferredCarWriter::finish`]. The file has no index.
+	pub fn new_v2(reserved: usize, mut writer: W) -> Result<Self, Error> {
+		let start = position(&mut writer)?;
+		writer.write_all(&V2_PRAGMA)?;
+		writer.write_all(&[0; V2_HEADER_LEN])?;
+		writer.write_all(&alloc::vec![0; reserved])?;
+		// The header is written by `finish`, the blocks follow the reserved
+		// space.
+		let header = CarHeader::new_v1(Vec::new());
+		Ok(Self {
+			writer: CarWriter::with_header_written(header, writer),
+			start,
+			layout: Layout::V2 { reserved },
+		})
+	}
+
+	/// Writes a block.
+	///
+	/// Returns the bytes written in this operation.
+	pub fn write<T>(&mut self, cid: Cid, data: T) -> Result<usize, Error>
+	where
+		T: AsRef<[u8]>,
+	{
+		self.writer.write(cid, data)
+	}
+
+	/// Flushes the underlying writer.
+	pub fn flush(&mut self) -> Result<(), Error> {
+		self.writer.flush()
+	}
+
+	/// Writes the header with the roots, flushes and returns the writer.
+	///
+	/// Fails if the header doesn't fit the reserved space. The writer is left
+	/// at the end of the file.
+	pub fn finish(self, roots: Vec<Cid>) -> Result<W, Error> {
+		let mut section = Vec::new();
+		let header = CarHeader::new_v1(roots).encode()?;
+		write_varint_usize(header.len(), &mut section)?;
+		section.extend_from_slice(&header);
+
+		let mut writer = self.writer.into_inner();
+		let end = position(&mut writer)?;
+		match self.layout {
+			Layout::V1 { header_len } => {
+				if section.len() != header_len {
+					return Err(Error::InvalidFile(format!(
+						"the roots need a header of {} bytes, but {} bytes are reserved",
+						section.len(),
+						header_len
+					)));
+				}
+				writer.seek(SeekFrom::Start(self.start))?;
+				writer.write_all(&section)?;
+			}
+			Layout::V2 { reserved } => {
+				if section.len() > reserved {
+					return Err(Error::InvalidFile(format!(
+						"the roots need a header of {} bytes, but only {} bytes are \
+						 reserved",
+						section.len(),
+						reserved
+					)));
+				}
+				let data_offset =
+					(V2_PRAGMA.len() + V2_HEADER_LEN + reserved - section.len()) as u64;
+				let data_size = end - self.start - data_offset;
+				writer.seek(SeekFrom::Start(self.start + V2_PRAGMA.len() as u64))?;
+				writer.write_all(&[0; 16])?;
+				writer.write_all(&data_offset.to_le_bytes())?;
+				writer.write_all(&data_size.to_le_bytes())?;
+				writer.write_all(&0u64.to_le_bytes())?;
+				writer.seek(SeekFrom::Start(self.start + data_offset))?;
+				writer.write_all(&section)?;
+			}
+		}
+		writer.seek(SeekFrom::Start(end))?;
+		writer.flush()?;
+		Ok(writer)
+	}
+}
+
+/// Returns the current position, fails clearly if the writer can't seek.
+// core2 has no `stream_position`.
+#[allow(clippy::seek_from_current)]
+fn position<W: Seek>(writer: &mut W) -> Result<u64, Error> {
+	writer.seek(SeekFrom::Current(0)).map_err(|e| {
+		Error::InvalidFile(format!("deferred roots need a seekable writer: {}", e))
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::{
+			super::{reader::CarReader, test_cid},
+			*,
+		},
+		crate::multihash::Multihash,
+		alloc::vec,
+		core2::io,
+	};
+
+	/// A growable, seekable buffer.
+	#[derive(Default)]
+	struct Buffer(Vec<u8>, usize);
+
+	impl Write for Buffer {
+		fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+			let end = self.1 + buf.len();
+			if end > self.0.len() {
+				self.0.resize(end, 0);
+			}
+			self.0[self.1..end].copy_from_slice(buf);
+			self.1 = end;
+			Ok(buf.len())
+		}
+
+		fn flush(&mut self) -> io::Result<()> {
+			Ok(())
+		}
+	}
+
+	impl Seek for Buffer {
+		fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+			self.1 = match pos {
+				SeekFrom::Start(pos) => pos as usize,
+				SeekFrom::End(pos) => (self.0.len() as i64 + pos) as usize,
+				SeekFrom::Current(pos) => (self.1 as i64 + pos) as usize,
+			};
+			Ok(self.1 as u64)
+		}
+	}
+
+	/// A stream that can't seek.
+	struct Stream;
+
+	impl Write for Stream {
+		fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+			Ok(buf.len())
+		}
+
+		fn flush(&mut self) -> io::Result<()> {
+			Ok(())
+		}
+	}
+
+	impl Seek for Stream {
+		fn seek(&mut self, _: SeekFrom) -> io::Result<u64> {
+			Err(io::ErrorKind::Unsupported.into())
+		}
+	}
+
+	fn blocks(reader: CarReader<&[u8]>) -> Vec<(Cid, Vec<u8>)> {
+		reader.into_iter().map(|block| block.unwrap()).collect()
+	}
+
+	#[test]
+	fn deferred_car_v1() {
+		let placeholder =
+			Cid::new_v1(0x71, Multihash::wrap(0x1e, &[0; 32]).unwrap());
+		let mut writer = DeferredCarWriter::new_v1(
+			CarHeader::new_v1(vec![placeholder]),
+			Buffer::default(),
+		)
+		.unwrap();
+		writer.write(test_cid(b"test"), b"test").unwrap();
+		writer.write(test_cid(b"foo"), b"foo").unwrap();
+		let buffer = writer.finish(vec![test_cid(b"foo")]).unwrap().0;
+
+		// The same as if the roots were known up front.
+		let mut expected = Vec::new();
+		let mut writer =
+			CarWriter::new(CarHeader::new_v1(vec![test_cid(b"foo")]), &mut expected);
+		writer.write(test_cid(b"test"), b"test").unwrap();
+		writer.write(test_cid(b"foo"), b"foo").unwrap();
+		writer.finish().unwrap();
+		assert_eq!(buffer, expected);
+
+		// Roots of another size don't fit.
+		let mut writer = DeferredCarWriter::new_v1(
+			CarHeader::new_v1(vec![placeholder]),
+			Buffer::default(),
+		)
+		.unwrap();
+		writer.write(test_cid(b"foo"), b"foo").unwrap();
+		assert!(matches!(
+			writer.finish(vec![test_cid(b"foo"), test_cid(b"test")]),
+			Err(Error::InvalidFile(_))
+		));
+	}
+
+	#[test]
+	fn deferred_car_v2() {
+		let mut writer = DeferredCarWriter::new_v2(128, Buffer::default()).unwrap();
+		writer.write(test_cid(b"test"), b"test").unwrap();
+		writer.write(test_cid(b"foo"), b"foo").unwrap();
+		let buffer = writer.finish(vec![test_cid(b"foo")]).unwrap().0;
+
+		assert_eq!(buffer[..11], V2_PRAGMA);
+		let u64_at =
+			|pos: usize| u64::from_le_bytes(buffer[pos..pos + 8].try_into().unwrap());
+		let (data_offset, data_size) = (u64_at(27) as usize, u64_at(35) as usize);
+		assert_eq!(u64_at(43), 0);
+		assert_eq!(data_offset + data_size, buffer.len());
+
+		let reader = CarReader::new(&buffer[data_offset..]).unwrap();
+		assert_eq!(reader.header().roots(), [test_cid(b"foo")]);
+		assert_eq!(blocks(reader), [
+			(test_cid(b"test"), b"test".to_vec()),
+			(test_cid(b"foo"), b"foo".to_vec())
+		]);
+
+		let writer = DeferredCarWriter::new_v2(8, Buffer::default()).unwrap();
+		assert!(matches!(
+			writer.finish(vec![test_cid(b"foo")]),
+			Err(Error::InvalidFile(_))
+		));
+	}
+
+	#[test]
+	fn deferred_car_errors() {
+		assert!(matches!(
+			DeferredCarWriter::new_v2(128, Stream),
+			Err(Error::InvalidFile(_))
+		));
+
+		// Plain streams need the roots up front.
+		let mut writer = CarWriter::new(CarHeader::new_v1(Vec::new()), Vec::new());
+		assert!(matches!(
+			writer.write(test_cid(b"foo"), b"foo"),
+			Err(Error::InvalidFile(_))
+		));
+
+		let writer = DeferredCarWriter::new_v2(128, Buffer::default()).unwrap();
+		assert!(matches!(
+			writer.finish(Vec::new()),
+			Err(Error::InvalidFile(_))
+		));
+	}
+}
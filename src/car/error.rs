@@ -1,5 +1,10 @@
 use {
-	crate::{alloc::string::ToString, cid, dag, multihash},
+	crate::{
+		alloc::string::ToString,
+		cid::{self, Cid},
+		dag,
+		multihash::{self, VerifyError},
+	},
 	alloc::string::String,
 	thiserror_core2::Error,
 };
@@ -17,6 +22,8 @@ pub enum Error {
 	Cbor(#[from] dag::error::CodecError),
 	#[error("ld read too large {0}")]
 	LdReadTooLarge(usize),
+	#[error("Block of {0} failed to verify: {1}")]
+	Verify(Cid, VerifyError),
 }
 
 impl From<cid::Error> for Error {
@@ -30,3 +37,29 @@ impl From<multihash::Error> for Error {
 		Error::Parsing(err.to_string())
 	}
 }
+
+/// Converts an error of an async reader or writer.
+#[cfg(feature = "async")]
+pub(crate) fn async_io_error<E: embedded_io_async::Error>(err: E) -> Error {
+	use {core2::io::ErrorKind as Core2, embedded_io_async::ErrorKind};
+
+	let kind = match err.kind() {
+		ErrorKind::NotFound => Core2::NotFound,
+		ErrorKind::PermissionDenied => Core2::PermissionDenied,
+		ErrorKind::ConnectionRefused => Core2::ConnectionRefused,
+		ErrorKind::ConnectionReset => Core2::ConnectionReset,
+		ErrorKind::ConnectionAborted => Core2::ConnectionAborted,
+		ErrorKind::NotConnected => Core2::NotConnected,
+		ErrorKind::AddrInUse => Core2::AddrInUse,
+		ErrorKind::AddrNotAvailable => Core2::AddrNotAvailable,
+		ErrorKind::BrokenPipe => Core2::BrokenPipe,
+		ErrorKind::AlreadyExists => Core2::AlreadyExists,
+		ErrorKind::InvalidInput => Core2::InvalidInput,
+		ErrorKind::InvalidData => Core2::InvalidData,
+		ErrorKind::TimedOut => Core2::TimedOut,
+		ErrorKind::Interrupted => Core2::Interrupted,
+		ErrorKind::WriteZero => Core2::WriteZero,
+		_ => Core2::Other,
+	};
+	Error::Io(kind.into())
+}
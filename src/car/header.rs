@@ -38,9 +38,20 @@ impl CarHeader {
 		Ok(CarHeader::V1(header))
 	}
 
+	/// Encodes the header.
+	///
+	/// Fails if there are no roots, roots that are only known after the
+	/// blocks are written need a [`DeferredCarWriter`](super::DeferredCarWriter).
 	pub fn encode(&self) -> Result<Vec<u8>, Error> {
 		match self {
 			CarHeader::V1(ref header) => {
+				if header.roots.is_empty() {
+					return Err(Error::InvalidFile(
+						"the header has no roots, use a DeferredCarWriter to write them \
+						 after the blocks"
+							.to_string(),
+					));
+				}
 				let res = dag::to_vec(header).expect("vec");
 				Ok(res)
 			}
@@ -1,12 +1,37 @@
 //! Implementation of the [car](https://ipld.io/specs/transport/car/) format.
+//!
+//! The `async` feature adds `AsyncCarReader` and `AsyncCarWriter`. Unlike
+//! the rest of the crate, which builds with Rust 1.70, it needs Rust 1.75 for
+//! async functions in traits.
 
+#[cfg(feature = "async")]
+mod async_reader;
+#[cfg(feature = "async")]
+mod async_writer;
+mod decoder;
+mod deferred;
 mod error;
 mod header;
 mod reader;
 mod util;
 mod writer;
 
+#[cfg(feature = "async")]
+pub use async_reader::AsyncCarReader;
+#[cfg(feature = "async")]
+pub use async_writer::AsyncCarWriter;
+pub use decoder::{CarDecoder, CarEvent};
+pub use deferred::DeferredCarWriter;
 pub use error::Error;
 pub use header::CarHeader;
 pub use reader::CarReader;
 pub use writer::CarWriter;
+
+/// Returns the DAG-CBOR CID of the data, with a BLAKE3 multihash.
+#[cfg(test)]
+fn test_cid(data: &[u8]) -> crate::cid::Cid {
+	let digest =
+		crate::multihash::Multihash::wrap(0x1e, blake3::hash(data).as_bytes())
+			.unwrap();
+	crate::cid::Cid::new_v1(0x71, digest)
+}
@@ -4,7 +4,7 @@ use {
 		header::CarHeader,
 		util::{ld_read, read_node},
 	},
-	crate::cid::Cid,
+	crate::{cid::Cid, multihash::CodeTable},
 	alloc::{string::ToString, vec::Vec},
 };
 
@@ -49,6 +49,21 @@ where
 	pub fn next_block(&mut self) -> Result<Option<(Cid, Vec<u8>)>, Error> {
 		read_node(&mut self.reader, &mut self.buffer)
 	}
+
+	/// Returns the next IPLD Block in the buffer, after verifying it against
+	/// its CID with the hash function from the given table.
+	pub fn next_block_with<T: CodeTable>(
+		&mut self,
+		table: &T,
+	) -> Result<Option<(Cid, Vec<u8>)>, Error> {
+		let block = self.next_block()?;
+		if let Some((cid, data)) = &block {
+			cid
+				.verify_with(table, data)
+				.map_err(|err| Error::Verify(*cid, err))?;
+		}
+		Ok(block)
+	}
 }
 
 impl<R: core2::io::Read> IntoIterator for CarReader<R> {
@@ -106,4 +121,31 @@ mod tests {
 		assert_eq!(files[1].0, cid_foo);
 		assert_eq!(files[1].1, b"foo");
 	}
+
+	#[cfg(feature = "blake3")]
+	#[test]
+	fn car_read_verified() {
+		use crate::multihash::{BuiltinCodeTable, VerifyError};
+
+		let table = BuiltinCodeTable;
+		let cid_foo = Cid::digest_v1_with(&table, 0x71, 0x1e, b"foo").unwrap();
+		let header = CarHeader::V1(CarHeaderV1::from(vec![cid_foo]));
+
+		let mut buffer = Vec::new();
+		let mut writer = CarWriter::new(header, &mut buffer);
+		writer.write(cid_foo, b"foo").unwrap();
+		writer.write(cid_foo, b"bar").unwrap();
+		writer.finish().unwrap();
+
+		let mut reader = CarReader::new(Cursor::new(&buffer)).unwrap();
+		assert_eq!(
+			reader.next_block_with(&table).unwrap(),
+			Some((cid_foo, b"foo".to_vec()))
+		);
+		assert!(matches!(
+			reader.next_block_with(&table),
+			Err(Error::Verify(cid, VerifyError::DigestMismatch)) if cid == cid_foo
+		));
+		assert_eq!(reader.next_block_with(&table).unwrap(), None);
+	}
 }
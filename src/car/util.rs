@@ -6,57 +6,39 @@ use core2::io::{Read, Write};
 /// Maximum size that is used for single node.
 pub(crate) const MAX_ALLOC: usize = 4 * 1024 * 1024;
 
+/// Reads a length-delimited section into the buffer.
+///
+/// Returns `Ok(None)` on `EOF` before the length prefix is complete.
 pub(crate) fn ld_read<R>(mut reader: R, buf: &mut Vec<u8>) -> Result<Option<&[u8]>, Error>
 where
     R: Read,
 {
-    let length: usize = match read_varint_usize(&mut reader) {
-        Ok(Some(len)) => len,
-        Ok(None) => return Ok(None),
-        Err(e) => {
-            return Err(Error::Parsing(e.to_string()));
+    let mut prefix = varint::encode::usize_buffer();
+    let mut len = 0;
+    let length = loop {
+        let n = reader
+            .read(&mut prefix[len..=len])
+            .map_err(|e| Error::Parsing(e.to_string()))?;
+        if n == 0 {
+            return Ok(None);
+        }
+        len += 1;
+        if let Some((length, _)) = ld_length(&prefix[..len])? {
+            break length;
         }
     };
-    if length > MAX_ALLOC {
-        return Err(Error::LdReadTooLarge(length));
-    }
-    if length > buf.len() {
-        buf.resize(length, 0);
-    }
 
+    let section = section_buf(buf, length);
     reader
-        .read_exact(&mut buf[..length])
+        .read_exact(section)
         .map_err(|e| Error::Parsing(e.to_string()))?;
 
-    Ok(Some(&buf[..length]))
-}
-
-/// Read a varint from the provided reader. Returns `Ok(None)` on unexpected `EOF`.
-pub fn read_varint_usize<R: Read + Unpin>(
-    mut reader: R,
-) -> Result<Option<usize>, varint::ReadError> {
-    let mut b = varint::encode::usize_buffer();
-    for i in 0..b.len() {
-        let n = reader.read(&mut b[i..i + 1])?;
-        if n == 0 {
-            return Ok(None);
-        }
-        if varint::decode::is_last(b[i]) {
-            let slice = &b[..=i];
-            let (num, _) = varint::decode::usize(slice)?;
-            return Ok(Some(num));
-        }
-    }
-    Err(varint::decode::Error::Overflow)?
+    Ok(Some(section))
 }
 
 /// Write the given number as varint to the provided writer.
 pub fn write_varint_usize<W: Write + Unpin>(num: usize, mut writer: W) -> core2::io::Result<usize> {
-    let mut buffer = varint::encode::usize_buffer();
-    let to_write = varint::encode::usize(num, &mut buffer);
-    writer.write_all(to_write)?;
-
-    Ok(to_write.len())
+    varint::write_usize(num, &mut writer)
 }
 
 pub(crate) fn read_node<R>(
@@ -67,15 +49,65 @@ where
     R: Read,
 {
     if let Some(buf) = ld_read(buf_reader, buf)? {
-        let mut cursor = core2::io::Cursor::new(buf);
-        let c = Cid::read_bytes(&mut cursor)?;
-        let pos = cursor.position() as usize;
-
-        return Ok(Some((c, buf[pos..].to_vec())));
+        return parse_node(buf).map(Some);
     }
     Ok(None)
 }
 
+/// Splits a length-delimited section into the CID and the block data.
+pub(crate) fn parse_node(buf: &[u8]) -> Result<(Cid, Vec<u8>), Error> {
+    let (c, data) = split_node(buf)?;
+    Ok((c, data.to_vec()))
+}
+
+/// Splits a length-delimited section into the CID and the borrowed block data.
+pub(crate) fn split_node(buf: &[u8]) -> Result<(Cid, &[u8]), Error> {
+    let mut cursor = core2::io::Cursor::new(buf);
+    let c = Cid::read_bytes(&mut cursor)?;
+    let pos = cursor.position() as usize;
+
+    Ok((c, &buf[pos..]))
+}
+
+/// Decodes the length prefix of a section at the start of the buffer.
+///
+/// Returns the length of the section and the rest of the buffer, or `Ok(None)`
+/// if the buffer doesn't contain the whole prefix yet. Sections that are
+/// larger than [`MAX_ALLOC`] are rejected.
+pub(crate) fn ld_length(buf: &[u8]) -> Result<Option<(usize, &[u8])>, Error> {
+    let (length, rest) = match varint::decode::usize(buf) {
+        Ok(decoded) => decoded,
+        Err(varint::decode::Error::Insufficient) => return Ok(None),
+        Err(e) => return Err(Error::Parsing(e.to_string())),
+    };
+    if length > MAX_ALLOC {
+        return Err(Error::LdReadTooLarge(length));
+    }
+    Ok(Some((length, rest)))
+}
+
+/// Returns the first `length` bytes of the buffer, growing it if needed.
+pub(crate) fn section_buf(buf: &mut Vec<u8>, length: usize) -> &mut [u8] {
+    if length > buf.len() {
+        buf.resize(length, 0);
+    }
+    &mut buf[..length]
+}
+
+/// Splits a length-delimited section off the start of the buffer.
+///
+/// Returns the section and the number of bytes it takes including the length,
+/// or `Ok(None)` if the buffer doesn't contain the whole section yet.
+pub(crate) fn ld_split(buf: &[u8]) -> Result<Option<(&[u8], usize)>, Error> {
+    let (length, rest) = match ld_length(buf)? {
+        Some(decoded) => decoded,
+        None => return Ok(None),
+    };
+    let prefix = buf.len() - rest.len();
+
+    Ok(rest.get(..length).map(|section| (section, prefix + length)))
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -115,4 +147,17 @@ mod tests {
         let read = ld_read(reader, &mut buffer);
         assert!(matches!(read, Err(Error::LdReadTooLarge(_))));
     }
+
+    #[test]
+    fn ld_read_prefix() {
+        let mut buffer = Vec::new();
+        // EOF within the prefix ends the file, a prefix that never ends is an error.
+        let read = ld_read(&[0x80][..], &mut buffer).unwrap();
+        assert!(read.is_none());
+        let read = ld_read(&[0xff; 16][..], &mut buffer);
+        assert!(matches!(read, Err(Error::Parsing(_))));
+        // The prefix is the same as the one of a buffered section.
+        assert!(matches!(ld_split(&[0xff; 16]), Err(Error::Parsing(_))));
+        assert_eq!(ld_split(&[0x80]).unwrap(), None);
+    }
 }
@@ -26,6 +26,14 @@ where
 		}
 	}
 
+	/// Creates a CarWriter for a sink that already contains the header.
+	pub(crate) fn with_header_written(header: CarHeader, writer: W) -> Self {
+		CarWriter {
+			is_header_written: true,
+			..Self::new(header, writer)
+		}
+	}
+
 	/// Forces the header to be written. Also called implicitly by `write`.
 	///
 	/// Returns the bytes written in this operation.
@@ -0,0 +1,298 @@
+use {
+	super::{
+		cid::Cid,
+		error::{Error, Result},
+		version::Version,
+	},
+	crate::{
+		multibase::{self, Base},
+		multihash::{CodeTable, MultihashRef, VerifyError},
+		varint::decode,
+	},
+	core::{cmp::Ordering, fmt, hash},
+};
+
+/// A CID that borrows the bytes it was parsed from.
+///
+/// The CID is validated when it's parsed, but nothing is copied. It compares
+/// and hashes the same way as [`Cid`], so both can be used interchangeably as
+/// keys.
+#[derive(Clone, Copy, Eq)]
+pub struct CidRef<'a> {
+	/// The version of CID.
+	version: Version,
+	/// The codec of CID.
+	codec: u64,
+	/// The multihash of CID.
+	hash: MultihashRef<'a>,
+	/// The encoded CID.
+	bytes: &'a [u8],
+}
+
+impl<'a> CidRef<'a> {
+	/// Parses a CID from bytes, without copying them.
+	///
+	/// The bytes need to contain exactly one CID.
+	pub fn from_bytes(bytes: &'a [u8]) -> Result<Self> {
+		let (cid, remaining) = Self::read(bytes)?;
+		if !remaining.is_empty() {
+			return Err(Error::ParsingError);
+		}
+		Ok(cid)
+	}
+
+	/// Parses a CID from the start of the bytes, without copying them.
+	///
+	/// Returns the CID and the bytes after it.
+	pub fn read(bytes: &'a [u8]) -> Result<(Self, &'a [u8])> {
+		let varint_u64 =
+			|bytes| decode::u64(bytes).map_err(|_| Error::VarIntDecodeError);
+		let (version, remaining) = varint_u64(bytes)?;
+		let (codec, _) = varint_u64(remaining)?;
+
+		// CIDv0 has the fixed `0x12 0x20` prefix
+		if [version, codec] == [0x12, 0x20] {
+			if bytes.len() < 34 {
+				return Err(Error::InputTooShort);
+			}
+			let (bytes, remaining) = bytes.split_at(34);
+			let (hash, _) = MultihashRef::read(bytes)?;
+			let cid = Self {
+				version: Version::V0,
+				codec: super::cid::DAG_PB,
+				hash,
+				bytes,
+			};
+			return Ok((cid, remaining));
+		}
+
+		match Version::try_from(version)? {
+			Version::V0 => Err(Error::InvalidExplicitCidV0),
+			Version::V1 => {
+				let (codec, remaining) = varint_u64(remaining)?;
+				let (hash, remaining) = MultihashRef::read(remaining)?;
+				let cid = Self {
+					version: Version::V1,
+					codec,
+					hash,
+					bytes: &bytes[..bytes.len() - remaining.len()],
+				};
+				Ok((cid, remaining))
+			}
+		}
+	}
+
+	/// Returns the cid version.
+	pub const fn version(&self) -> Version {
+		self.version
+	}
+
+	/// Returns the cid codec.
+	pub const fn codec(&self) -> u64 {
+		self.codec
+	}
+
+	/// Returns the cid multihash.
+	pub const fn hash(&self) -> MultihashRef<'a> {
+		self.hash
+	}
+
+	/// Verifies that the cid is the address of the given data, see
+	/// [`Cid::verify`].
+	#[cfg(any(feature = "sha2", feature = "blake3"))]
+	pub fn verify(&self, data: &[u8]) -> core::result::Result<(), VerifyError> {
+		self.hash.verify(data)
+	}
+
+	/// Verifies that the cid is the address of the given data, see
+	/// [`Cid::verify_with`].
+	pub fn verify_with<T: CodeTable>(
+		&self,
+		table: &T,
+		data: &[u8],
+	) -> core::result::Result<(), VerifyError> {
+		self.hash.verify_with(table, data)
+	}
+
+	/// Returns the encoded bytes of the CID.
+	pub const fn as_bytes(&self) -> &'a [u8] {
+		self.bytes
+	}
+
+	/// Returns the length in bytes of the encoded CID.
+	pub const fn encoded_len(&self) -> usize {
+		self.bytes.len()
+	}
+
+	/// Copies the CID into an owned one.
+	///
+	/// This fails if the digest is larger than the allocated size `S`.
+	pub fn to_cid<const S: usize>(&self) -> Result<Cid<S>> {
+		Cid::new(self.version, self.codec, self.hash.to_multihash()?)
+	}
+}
+
+impl<'a> TryFrom<&'a [u8]> for CidRef<'a> {
+	type Error = Error;
+
+	fn try_from(bytes: &'a [u8]) -> Result<Self> {
+		Self::from_bytes(bytes)
+	}
+}
+
+impl<const S: usize> TryFrom<CidRef<'_>> for Cid<S> {
+	type Error = Error;
+
+	fn try_from(cid: CidRef<'_>) -> Result<Self> {
+		cid.to_cid()
+	}
+}
+
+impl PartialEq for CidRef<'_> {
+	fn eq(&self, other: &Self) -> bool {
+		self.version == other.version
+			&& self.codec == other.codec
+			&& self.hash == other.hash
+	}
+}
+
+impl<const S: usize> PartialEq<Cid<S>> for CidRef<'_> {
+	fn eq(&self, other: &Cid<S>) -> bool {
+		self.version == other.version()
+			&& self.codec == other.codec()
+			&& self.hash == *other.hash()
+	}
+}
+
+// Hashes the same fields in the same order as the derived implementation of
+// `Cid`.
+impl hash::Hash for CidRef<'_> {
+	fn hash<T: hash::Hasher>(&self, state: &mut T) {
+		self.version.hash(state);
+		self.codec.hash(state);
+		self.hash.hash(state);
+	}
+}
+
+impl PartialOrd for CidRef<'_> {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for CidRef<'_> {
+	fn cmp(&self, other: &Self) -> Ordering {
+		(self.version, self.codec, self.hash).cmp(&(
+			other.version,
+			other.codec,
+			other.hash,
+		))
+	}
+}
+
+impl fmt::Display for CidRef<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let output = match self.version {
+			Version::V0 => Base::Base58Btc.encode(self.bytes),
+			Version::V1 => multibase::encode(Base::Base32Lower, self.bytes),
+		};
+		f.write_str(&output)
+	}
+}
+
+impl fmt::Debug for CidRef<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "CidRef({})", self)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::*,
+		alloc::{string::ToString, vec::Vec},
+		core::hash::{Hash, Hasher},
+	};
+
+	const V0: &str = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n";
+	const V1: &str =
+		"bafyreibjo4xmgaevkgud7mbifn3dzp4v4lyaui4yvqp3f2bqwtxcjrdqg4";
+
+	/// Collects everything that is hashed.
+	#[derive(Default)]
+	struct CollectingHasher(Vec<u8>);
+
+	impl Hasher for CollectingHasher {
+		fn finish(&self) -> u64 {
+			0
+		}
+
+		fn write(&mut self, bytes: &[u8]) {
+			self.0.extend_from_slice(bytes);
+		}
+	}
+
+	fn hashed<T: Hash>(value: &T) -> Vec<u8> {
+		let mut hasher = CollectingHasher::default();
+		value.hash(&mut hasher);
+		hasher.0
+	}
+
+	#[test]
+	fn test_cid_ref() {
+		for cid_str in [V0, V1] {
+			let cid = Cid::<64>::try_from(cid_str).unwrap();
+			let bytes = cid.to_bytes();
+			let cid_ref = CidRef::from_bytes(&bytes).unwrap();
+			assert_eq!(cid_ref.version(), cid.version());
+			assert_eq!(cid_ref.codec(), cid.codec());
+			assert_eq!(cid_ref.hash(), *cid.hash());
+			assert_eq!(cid_ref.as_bytes(), bytes);
+			assert_eq!(cid_ref.encoded_len(), cid.encoded_len());
+			assert_eq!(cid_ref, cid);
+			assert_eq!(hashed(&cid_ref), hashed(&cid));
+			assert_eq!(cid_ref.to_string(), cid_str);
+			assert_eq!(cid_ref.to_cid::<64>().unwrap(), cid);
+			assert!(cid_ref.to_cid::<16>().is_err());
+		}
+	}
+
+	#[test]
+	fn test_cid_ref_ord() {
+		let v0 = Cid::<64>::try_from(V0).unwrap();
+		let v1 = Cid::<64>::try_from(V1).unwrap();
+		let (v0_bytes, v1_bytes) = (v0.to_bytes(), v1.to_bytes());
+		let v0_ref = CidRef::from_bytes(&v0_bytes).unwrap();
+		let v1_ref = CidRef::from_bytes(&v1_bytes).unwrap();
+		assert_eq!(v0_ref.cmp(&v1_ref), v0.cmp(&v1));
+		assert_eq!(v1_ref.cmp(&v0_ref), v1.cmp(&v0));
+	}
+
+	#[test]
+	fn test_cid_ref_from_buffer() {
+		let v0 = Cid::<64>::try_from(V0).unwrap();
+		let v1 = Cid::<64>::try_from(V1).unwrap();
+		let buffer = [v1.to_bytes(), v0.to_bytes(), v1.to_bytes()].concat();
+
+		let mut remaining = &buffer[..];
+		let mut cids = Vec::new();
+		while !remaining.is_empty() {
+			let (cid, rest) = CidRef::read(remaining).unwrap();
+			cids.push(cid);
+			remaining = rest;
+		}
+		assert_eq!(cids, [v1, v0, v1]);
+		assert!(CidRef::from_bytes(&buffer).is_err());
+	}
+
+	#[test]
+	fn test_cid_ref_invalid() {
+		let bytes = Cid::<64>::try_from(V1).unwrap().to_bytes();
+		assert!(CidRef::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+		assert!(CidRef::from_bytes(&[]).is_err());
+		assert!(CidRef::from_bytes(&[0x12, 0x20, 0x01]).is_err());
+		// Explicit CIDv0 and unknown versions.
+		assert!(CidRef::from_bytes(&[0x00, 0x70, 0x12, 0x00]).is_err());
+		assert!(CidRef::from_bytes(&[0x02, 0x70, 0x12, 0x00]).is_err());
+	}
+}
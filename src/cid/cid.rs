@@ -13,7 +13,8 @@ use {
 	},
 	crate::{
 		multibase::{self, encode as base_encode, Base},
-		multihash::Multihash,
+		multicodec::{CodeName, Multicodec},
+		multihash::{self, CodeTable, Multihash, VerifyError},
 		varint::{self, encode as varint_encode},
 	},
 	alloc::{
@@ -47,9 +48,9 @@ pub(crate) fn varint_read_u64<R: io::Read>(mut r: R) -> Result<u64> {
 }
 
 /// DAG-PB multicodec code
-const DAG_PB: u64 = 0x70;
+pub(crate) const DAG_PB: u64 = Multicodec::DagPb.code();
 /// The SHA_256 multicodec code
-pub(crate) const SHA2_256: u64 = 0x12;
+pub(crate) const SHA2_256: u64 = Multicodec::Sha2_256.code();
 
 /// Representation of a CID.
 ///
@@ -86,6 +87,17 @@ impl<const S: usize> Cid<S> {
 		}
 	}
 
+	/// Create a new CIDv1 of the data, hashed with the hash function of the
+	/// code from the given table.
+	pub fn digest_v1_with<T: CodeTable>(
+		table: &T,
+		codec: u64,
+		code: u64,
+		data: &[u8],
+	) -> core::result::Result<Self, multihash::Error> {
+		Ok(Self::new_v1(codec, table.digest(code, data)?))
+	}
+
 	/// Create a new CID.
 	pub const fn new(
 		version: Version,
@@ -116,6 +128,37 @@ impl<const S: usize> Cid<S> {
 		}
 	}
 
+	/// Convert a CIDv1 to a CIDv0. Returns unchanged if already a CIDv0.
+	///
+	/// This only succeeds for DAG-PB CIDs with a SHA2-256 multihash of 32 bytes,
+	/// otherwise [`Error::InvalidCidV0Codec`] or
+	/// [`Error::InvalidCidV0Multihash`] is returned.
+	pub const fn into_v0(self) -> Result<Self> {
+		match self.version {
+			Version::V0 => Ok(self),
+			Version::V1 => {
+				if self.codec != DAG_PB {
+					return Err(Error::InvalidCidV0Codec);
+				}
+				Self::new_v0(self.hash)
+			}
+		}
+	}
+
+	/// Returns the CIDv1 form of the CID.
+	///
+	/// The CIDv0 and CIDv1 forms of the same content have the same canonical
+	/// form, which makes it a good key for lookups with mixed-version inputs.
+	pub const fn to_canonical(&self) -> Self {
+		Self::new_v1(self.codec, self.hash)
+	}
+
+	/// Returns whether both CIDs address the same content, regardless of their
+	/// version.
+	pub fn eq_ignore_version<const R: usize>(&self, other: &Cid<R>) -> bool {
+		self.codec == other.codec && self.hash == other.hash
+	}
+
 	/// Returns the cid version.
 	pub const fn version(&self) -> Version {
 		self.version
@@ -131,6 +174,59 @@ impl<const S: usize> Cid<S> {
 		&self.hash
 	}
 
+	/// Verifies that the cid is the address of the given data.
+	///
+	/// Only the multihash is checked, see [`Multihash::verify`].
+	#[cfg(any(feature = "sha2", feature = "blake3"))]
+	pub fn verify(&self, data: &[u8]) -> core::result::Result<(), VerifyError> {
+		self.hash.verify(data)
+	}
+
+	/// Verifies that the cid is the address of the given data, with the hash
+	/// function from the given table.
+	pub fn verify_with<T: CodeTable>(
+		&self,
+		table: &T,
+		data: &[u8],
+	) -> core::result::Result<(), VerifyError> {
+		self.hash.verify_with(table, data)
+	}
+
+	/// Parses a CID from bytes, in a const context.
+	///
+	/// Unlike [`Cid::try_from`], the error doesn't need to be dropped, which
+	/// isn't possible at compile time.
+	pub(crate) const fn from_bytes_const(
+		bytes: &[u8],
+	) -> core::result::Result<Self, &'static str> {
+		// CIDv0 has the fixed `0x12 0x20` prefix
+		if bytes.len() == 34 && bytes[0] == 0x12 && bytes[1] == 0x20 {
+			return match Multihash::from_bytes_const(bytes) {
+				Ok(hash) => Ok(Self {
+					version: Version::V0,
+					codec: DAG_PB,
+					hash,
+				}),
+				Err(err) => Err(err),
+			};
+		}
+		let (version, bytes) = match varint::decode::u64_const(bytes) {
+			Ok(decoded) => decoded,
+			Err(_) => return Err("invalid CID version"),
+		};
+		if version != 1 {
+			return Err("unsupported CID version");
+		}
+		let (codec, bytes) = match varint::decode::u64_const(bytes) {
+			Ok(decoded) => decoded,
+			Err(_) => return Err("invalid CID codec"),
+		};
+		match Multihash::from_bytes_const(bytes) {
+			Ok(hash) => Ok(Self::new_v1(codec, hash)),
+			Err(err) => Err(err),
+		}
+	}
+
 	/// Reads the bytes from a byte stream.
 	pub fn read_bytes<R: io::Read>(mut r: R) -> Result<Self> {
 		let version = varint_read_u64(&mut r)?;
@@ -204,6 +300,53 @@ impl<const S: usize> Cid<S> {
 		bytes
 	}
 
+	/// Writes the bytes into the given buffer, returns the number of bytes
+	/// written.
+	///
+	/// Fails with [`Error::BufferTooSmall`] if the bytes don't fit, see
+	/// [`Cid::encoded_len`].
+	pub fn write_to_slice(&self, buf: &mut [u8]) -> Result<usize> {
+		let len = self.encoded_len();
+		let buf = buf.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+		self.write_bytes(buf)
+	}
+
+	/// Returns the encoded bytes of the `Cid` without allocating.
+	pub fn to_cid_bytes(&self) -> CidBytes {
+		let mut bytes = CidBytes {
+			bytes: [0; CidBytes::CAPACITY],
+			len: 0,
+		};
+		bytes.len = self
+			.write_to_slice(&mut bytes.bytes)
+			.expect("every CID fits into the capacity") as u16;
+		bytes
+	}
+
+	/// Encodes the `Cid` as multibase string into the given buffer, without
+	/// allocating.
+	///
+	/// Returns the part of the buffer that contains the string. CIDv0 can only
+	/// be encoded as Base58Btc.
+	pub fn encode_to_str<'a>(
+		&self,
+		base: Base,
+		buf: &'a mut [u8],
+	) -> Result<&'a str> {
+		let bytes = self.to_cid_bytes();
+		let len = match self.version {
+			Version::V0 => {
+				if base != Base::Base58Btc {
+					return Err(Error::InvalidCidV0Base);
+				}
+				base.encode_to_slice(&bytes, buf)?
+			}
+			Version::V1 => multibase::encode_to_slice(base, &bytes, buf)?,
+		};
+		// Only the identity base may produce invalid UTF-8.
+		core::str::from_utf8(&buf[..len]).map_err(|_| Error::ParsingError)
+	}
+
 	#[allow(clippy::wrong_self_convention)]
 	fn to_string_v0(&self) -> String {
 		Base::Base58Btc.encode(self.hash.to_bytes())
@@ -255,6 +398,65 @@ impl<const S: usize> Default for Cid<S> {
 	}
 }
 
+/// The encoded bytes of a CID, stored inline.
+///
+/// It's the allocation-free counterpart of [`Cid::to_bytes`]. The capacity is
+/// large enough for any CID, regardless of its digest size.
+#[derive(Clone, Copy)]
+pub struct CidBytes {
+	bytes: [u8; CidBytes::CAPACITY],
+	len: u16,
+}
+
+impl CidBytes {
+	/// The maximum length of an encoded CID: the version, the codec, the
+	/// multihash code and size, and the largest possible digest.
+	pub const CAPACITY: usize = 1
+		+ varint_encode::U64_LEN
+		+ varint_encode::U64_LEN
+		+ varint_encode::U8_LEN
+		+ u8::MAX as usize;
+
+	/// Returns the encoded bytes.
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.bytes[..usize::from(self.len)]
+	}
+}
+
+impl core::ops::Deref for CidBytes {
+	type Target = [u8];
+
+	fn deref(&self) -> &[u8] {
+		self.as_bytes()
+	}
+}
+
+impl AsRef<[u8]> for CidBytes {
+	fn as_ref(&self) -> &[u8] {
+		self.as_bytes()
+	}
+}
+
+impl PartialEq for CidBytes {
+	fn eq(&self, other: &Self) -> bool {
+		self.as_bytes() == other.as_bytes()
+	}
+}
+
+impl Eq for CidBytes {}
+
+impl core::hash::Hash for CidBytes {
+	fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+		self.as_bytes().hash(state);
+	}
+}
+
+impl core::fmt::Debug for CidBytes {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_tuple("CidBytes").field(&self.as_bytes()).finish()
+	}
+}
+
 impl<const S: usize> core::fmt::Display for Cid<S> {
 	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
 		let output = match self.version {
@@ -270,7 +472,7 @@ impl<const S: usize> core::fmt::Debug for Cid<S> {
 		if f.alternate() {
 			f.debug_struct("Cid")
 				.field("version", &self.version())
-				.field("codec", &self.codec())
+				.field("codec", &CodeName(self.codec()))
 				.field("hash", self.hash())
 				.finish()
 		} else {
@@ -405,10 +607,152 @@ mod tests {
 		txt.retain(|c| !c.is_whitespace());
 		assert_eq!(
 			&txt,
-			"Cid{version:V1,codec:113,hash:Multihash{code:18,size:32,digest:[41,119,\
-			 46,195,0,149,81,168,63,176,40,43,118,60,191,149,226,240,10,35,152,172,\
-			 31,178,232,48,180,238,36,196,112,55,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,\
-			 0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,],},}"
+			"Cid{version:V1,codec:dag-cbor(0x71),hash:Multihash{code:18,size:32,\
+			 digest:[41,119,46,195,0,149,81,168,63,176,40,43,118,60,191,149,226,240,\
+			 10,35,152,172,31,178,232,48,180,238,36,196,112,55,0,0,0,0,0,0,0,0,0,0,\
+			 0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,],},}"
+		);
+	}
+
+	#[test]
+	fn test_write_to_slice() {
+		use {super::Cid, crate::cid::Error};
+		for cid_str in [
+			"QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n",
+			"bafyreibjo4xmgaevkgud7mbifn3dzp4v4lyaui4yvqp3f2bqwtxcjrdqg4",
+		] {
+			let cid = Cid::<64>::try_from(cid_str).unwrap();
+			let bytes = cid.to_bytes();
+			assert_eq!(cid.to_cid_bytes().as_bytes(), bytes);
+
+			let mut buf = [0; 64];
+			assert_eq!(cid.write_to_slice(&mut buf).unwrap(), bytes.len());
+			assert_eq!(&buf[..bytes.len()], bytes);
+			assert!(matches!(
+				cid.write_to_slice(&mut buf[..bytes.len() - 1]),
+				Err(Error::BufferTooSmall)
+			));
+		}
+	}
+
+	#[test]
+	fn test_encode_to_str() {
+		use {
+			super::Cid,
+			crate::{cid::Error, multibase::Base},
+		};
+		let cid = Cid::<64>::try_from(
+			"bafyreibjo4xmgaevkgud7mbifn3dzp4v4lyaui4yvqp3f2bqwtxcjrdqg4",
+		)
+		.unwrap();
+		let mut buf = [0; 512];
+		for code in "079fFbBcCvVtThkKZzmMuU🚀".chars() {
+			let base = Base::from_code(code).unwrap();
+			let expected = cid.to_string_of_base(base).unwrap();
+			assert_eq!(cid.encode_to_str(base, &mut buf).unwrap(), expected);
+			assert!(matches!(
+				cid.encode_to_str(base, &mut buf[..expected.len() - 1]),
+				Err(Error::BufferTooSmall)
+			));
+		}
+
+		let v0 =
+			Cid::<64>::try_from("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n")
+				.unwrap();
+		assert_eq!(
+			v0.encode_to_str(Base::Base58Btc, &mut buf).unwrap(),
+			"QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n"
 		);
+		assert!(matches!(
+			v0.encode_to_str(Base::Base32Lower, &mut buf),
+			Err(Error::InvalidCidV0Base)
+		));
+	}
+
+	#[cfg(feature = "sha2")]
+	#[test]
+	fn test_verify() {
+		use {
+			super::Cid,
+			crate::{
+				cid::CidRef,
+				multihash::{BuiltinCodeTable, Multihash, VerifyError},
+			},
+			sha2::{Digest, Sha256},
+		};
+		let hash = Multihash::wrap(0x12, &Sha256::digest(b"block")).unwrap();
+		let cid = Cid::<64>::new_v1(0x55, hash);
+		assert_eq!(cid.verify(b"block"), Ok(()));
+		assert_eq!(cid.verify(b"other"), Err(VerifyError::DigestMismatch));
+		assert_eq!(cid.verify_with(&BuiltinCodeTable, b"block"), Ok(()));
+		assert_eq!(
+			Cid::digest_v1_with(&BuiltinCodeTable, 0x55, 0x12, b"block").unwrap(),
+			cid
+		);
+		assert!(Cid::<64>::digest_v1_with(
+			&BuiltinCodeTable,
+			0x55,
+			0x300000,
+			b"block"
+		)
+		.is_err());
+
+		let bytes = cid.to_bytes();
+		let cid_ref = CidRef::from_bytes(&bytes).unwrap();
+		assert_eq!(cid_ref.verify(b"block"), Ok(()));
+		assert_eq!(cid_ref.verify(b"other"), Err(VerifyError::DigestMismatch));
+		assert_eq!(cid_ref.verify_with(&BuiltinCodeTable, b"block"), Ok(()));
+	}
+
+	#[test]
+	fn test_into_v0() {
+		use {
+			super::Cid,
+			crate::{cid::Error, multihash::Multihash},
+		};
+		let v0 =
+			Cid::<64>::try_from("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n")
+				.unwrap();
+		let v1 = v0.into_v1().unwrap();
+		assert_eq!(v1.into_v0().unwrap(), v0);
+		assert_eq!(v0.into_v0().unwrap(), v0);
+
+		let dag_cbor = Cid::<64>::new_v1(0x71, *v0.hash());
+		assert!(matches!(dag_cbor.into_v0(), Err(Error::InvalidCidV0Codec)));
+		let sha2_512 =
+			Cid::<64>::new_v1(0x70, Multihash::wrap(0x13, &[0; 64]).unwrap());
+		assert!(matches!(
+			sha2_512.into_v0(),
+			Err(Error::InvalidCidV0Multihash)
+		));
+		let truncated = Cid::<64>::new_v1(0x70, v0.hash().truncate(20));
+		assert!(matches!(
+			truncated.into_v0(),
+			Err(Error::InvalidCidV0Multihash)
+		));
+	}
+
+	#[test]
+	fn test_eq_ignore_version() {
+		use super::Cid;
+		let v0 =
+			Cid::<64>::try_from("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n")
+				.unwrap();
+		let v1 = v0.into_v1().unwrap();
+		assert_ne!(v0, v1);
+		assert!(v0.eq_ignore_version(&v1));
+		assert!(v1.eq_ignore_version(
+			&v0
+				.hash()
+				.resize::<32>()
+				.map(|hash| { Cid::new_v0(hash).unwrap() })
+				.unwrap()
+		));
+		assert_eq!(v0.to_canonical(), v1);
+		assert_eq!(v1.to_canonical(), v1);
+
+		let dag_cbor = Cid::<64>::new_v1(0x71, *v0.hash());
+		assert!(!dag_cbor.eq_ignore_version(&v0));
+		assert_ne!(dag_cbor.to_canonical(), v0.to_canonical());
 	}
 }
@@ -30,6 +30,22 @@ pub enum Error {
 	Io(io::Error),
 	/// Invalid explicit CIDv0.
 	InvalidExplicitCidV0,
+	/// The output buffer is too small.
+	BufferTooSmall,
+	/// The data is larger than the inline policy allows.
+	InlineDataTooLarge {
+		/// The size of the data.
+		size: usize,
+		/// The maximum size of inlined data.
+		max: usize,
+	},
+	/// The digest doesn't fit into the allocated size of the multihash.
+	DigestTooLarge {
+		/// The size of the digest.
+		size: usize,
+		/// The allocated size of the multihash.
+		capacity: usize,
+	},
 }
 
 impl core2::error::Error for Error {}
@@ -48,6 +64,21 @@ impl fmt::Display for Error {
 			VarIntDecodeError => "Failed to decode unsigned varint format",
 			Io(err) => return write!(f, "{}", err),
 			InvalidExplicitCidV0 => "CIDv0 cannot be specified in CIDv1 format",
+			BufferTooSmall => "Output buffer too small",
+			InlineDataTooLarge { size, max } => {
+				return write!(
+					f,
+					"Inline data of {} bytes exceeds the maximum of {} bytes",
+					size, max
+				)
+			}
+			DigestTooLarge { size, capacity } => {
+				return write!(
+					f,
+					"Digest of {} bytes exceeds the allocated size of {} bytes",
+					size, capacity
+				)
+			}
 		};
 
 		f.write_str(error)
@@ -55,8 +86,11 @@ impl fmt::Display for Error {
 }
 
 impl From<multibase::Error> for Error {
-	fn from(_: multibase::Error) -> Error {
-		Error::ParsingError
+	fn from(err: multibase::Error) -> Error {
+		match err {
+			multibase::Error::BufferTooSmall => Error::BufferTooSmall,
+			_ => Error::ParsingError,
+		}
 	}
 }
 
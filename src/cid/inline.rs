@@ -0,0 +1,154 @@
+//! CIDs that inline their data with the identity multihash.
+
+use {
+	super::{
+		borrowed::CidRef,
+		cid::Cid,
+		error::{Error, Result},
+	},
+	crate::{multicodec::Multicodec, multihash::Multihash},
+};
+
+/// The code of the identity multihash.
+const IDENTITY: u64 = Multicodec::Identity.code();
+
+/// Limits the size of the data that is inlined into a CID.
+///
+/// Inlining only makes sense for small blocks, larger ones should be hashed
+/// and stored separately.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct InlinePolicy {
+	max_size: usize,
+}
+
+impl InlinePolicy {
+	/// The default maximum size of inlined data in bytes.
+	pub const DEFAULT_MAX_SIZE: usize = 32;
+
+	/// Creates a policy that inlines up to `max_size` bytes.
+	pub const fn new(max_size: usize) -> Self {
+		Self { max_size }
+	}
+
+	/// Returns the maximum size of inlined data in bytes.
+	pub const fn max_size(&self) -> usize {
+		self.max_size
+	}
+
+	/// Returns whether data of the given size may be inlined.
+	pub const fn allows(&self, size: usize) -> bool {
+		size <= self.max_size
+	}
+
+	/// Creates a CIDv1 that inlines the data.
+	///
+	/// Fails with [`Error::InlineDataTooLarge`] if the data is larger than the
+	/// policy allows, or with [`Error::DigestTooLarge`] if it doesn't fit into
+	/// the allocated digest size `S`.
+	pub fn new_cid<const S: usize>(
+		&self,
+		codec: u64,
+		data: &[u8],
+	) -> Result<Cid<S>> {
+		if !self.allows(data.len()) {
+			return Err(Error::InlineDataTooLarge {
+				size: data.len(),
+				max: self.max_size,
+			});
+		}
+		if data.len() > S {
+			return Err(Error::DigestTooLarge {
+				size: data.len(),
+				capacity: S,
+			});
+		}
+		let hash = Multihash::wrap(IDENTITY, data)?;
+		Ok(Cid::new_v1(codec, hash))
+	}
+}
+
+impl Default for InlinePolicy {
+	fn default() -> Self {
+		Self::new(Self::DEFAULT_MAX_SIZE)
+	}
+}
+
+impl<const S: usize> Cid<S> {
+	/// Creates a CIDv1 that inlines the data, with the default
+	/// [`InlinePolicy`].
+	pub fn new_inline(codec: u64, data: &[u8]) -> Result<Self> {
+		InlinePolicy::default().new_cid(codec, data)
+	}
+
+	/// Returns whether the data is inlined, i.e. the multihash is the identity.
+	pub const fn is_inline(&self) -> bool {
+		self.hash().code() == IDENTITY
+	}
+
+	/// Returns the inlined data, or `None` if the CID isn't inline.
+	///
+	/// The data of an inline CID doesn't need to be fetched from a block store.
+	pub fn inline_data(&self) -> Option<&[u8]> {
+		self.is_inline().then(|| self.hash().digest())
+	}
+}
+
+impl<'a> CidRef<'a> {
+	/// Returns whether the data is inlined, see [`Cid::is_inline`].
+	pub const fn is_inline(&self) -> bool {
+		self.hash().code() == IDENTITY
+	}
+
+	/// Returns the inlined data, see [`Cid::inline_data`].
+	pub fn inline_data(&self) -> Option<&'a [u8]> {
+		self.is_inline().then(|| self.hash().digest())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, crate::multihash::BuiltinCodeTable};
+
+	const RAW: u64 = 0x55;
+
+	#[test]
+	fn test_inline() {
+		let cid = Cid::<64>::new_inline(RAW, b"hello").unwrap();
+		assert!(cid.is_inline());
+		assert_eq!(cid.inline_data(), Some(&b"hello"[..]));
+		assert!(cid.verify_with(&BuiltinCodeTable, b"hello").is_ok());
+		assert!(cid.verify_with(&BuiltinCodeTable, b"hello!").is_err());
+
+		let bytes = cid.to_bytes();
+		let cid_ref = CidRef::from_bytes(&bytes).unwrap();
+		assert!(cid_ref.is_inline());
+		assert_eq!(cid_ref.inline_data(), Some(&b"hello"[..]));
+
+		let hashed =
+			Cid::<64>::new_v1(RAW, Multihash::wrap(0x12, &[0; 32]).unwrap());
+		assert!(!hashed.is_inline());
+		assert_eq!(hashed.inline_data(), None);
+	}
+
+	#[test]
+	fn test_inline_policy() {
+		let data = [1; 40];
+		assert!(matches!(
+			Cid::<64>::new_inline(RAW, &data),
+			Err(Error::InlineDataTooLarge { size: 40, max: 32 })
+		));
+
+		let policy = InlinePolicy::new(100);
+		assert!(policy.allows(100));
+		assert!(!policy.allows(101));
+		let cid = policy.new_cid::<64>(RAW, &data).unwrap();
+		assert_eq!(cid.inline_data(), Some(&data[..]));
+		assert!(matches!(
+			policy.new_cid::<32>(RAW, &data),
+			Err(Error::DigestTooLarge {
+				size: 40,
+				capacity: 32
+			})
+		));
+	}
+}
@@ -0,0 +1,220 @@
+//! Human readable breakdown of a CID.
+
+use {
+	super::{cid::Cid, error::Result, version::Version},
+	crate::{
+		multibase::Base,
+		multicodec::{CodeName, Multicodec},
+	},
+	alloc::{
+		format,
+		string::{String, ToString},
+		vec::Vec,
+	},
+	core::{fmt, str::FromStr},
+};
+
+/// The parts of a CID, see [`Cid::inspect`].
+///
+/// Parse a CID string into an `Inspection` to also know the multibase it was
+/// encoded with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Inspection {
+	/// The CID in its string form.
+	pub cid: String,
+	/// The multibase of the string the CID was parsed from, if known.
+	pub base: Option<Base>,
+	/// The version of the CID.
+	pub version: Version,
+	/// The codec of the CID.
+	pub codec: u64,
+	/// The name of the codec, if it's in the multicodec table.
+	pub codec_name: Option<&'static str>,
+	/// The code of the hash function.
+	pub hash_code: u64,
+	/// The name of the hash function, if it's in the multicodec table.
+	pub hash_name: Option<&'static str>,
+	/// The digest of the multihash.
+	pub digest: Vec<u8>,
+	/// The CIDv0 form, if the CID can be represented as CIDv0.
+	pub v0: Option<String>,
+	/// The CIDv1 form.
+	pub v1: String,
+}
+
+impl Inspection {
+	/// Returns the digest as lowercase hex string.
+	pub fn digest_hex(&self) -> String {
+		Base::Base16Lower.encode(&self.digest)
+	}
+
+	/// Returns the "human readable CID", e.g.
+	/// `base32 - cidv1 - dag-cbor - (sha2-256 : 256 : 2977...)`.
+	///
+	/// If the multibase isn't known, the default one of the CID version is
+	/// used.
+	pub fn human_readable(&self) -> String {
+		format!(
+			"{} - {} - {} - ({} : {} : {})",
+			self.base_or_default().name(),
+			version_name(self.version),
+			CodeName(self.codec),
+			CodeName(self.hash_code),
+			self.digest.len() * 8,
+			Base::Base16Upper.encode(&self.digest),
+		)
+	}
+
+	fn base_or_default(&self) -> Base {
+		self.base.unwrap_or(match self.version {
+			Version::V0 => Base::Base58Btc,
+			Version::V1 => Base::Base32Lower,
+		})
+	}
+}
+
+impl fmt::Display for Inspection {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		writeln!(f, "CID:            {}", self.cid)?;
+		match self.base {
+			Some(base) => {
+				writeln!(f, "Multibase:      {} ({})", base.name(), base.code())?
+			}
+			None => writeln!(f, "Multibase:      unknown")?,
+		}
+		writeln!(f, "Version:        {}", version_name(self.version))?;
+		writeln!(f, "Codec:          {:?}", CodeName(self.codec))?;
+		writeln!(f, "Multihash:      {:?}", CodeName(self.hash_code))?;
+		writeln!(f, "Digest length:  {}", self.digest.len())?;
+		writeln!(f, "Digest:         {}", self.digest_hex())?;
+		match &self.v0 {
+			Some(v0) => writeln!(f, "CIDv0:          {}", v0)?,
+			None => writeln!(f, "CIDv0:          not possible")?,
+		}
+		writeln!(f, "CIDv1:          {}", self.v1)?;
+		write!(f, "Human readable: {}", self.human_readable())
+	}
+}
+
+impl FromStr for Inspection {
+	type Err = super::Error;
+
+	/// Parses a CID string, the multibase it's encoded with is recorded.
+	fn from_str(cid_str: &str) -> Result<Self> {
+		let cid = crate::cid::Cid::try_from(cid_str)?;
+		// Strip a path prefix the same way parsing the CID does.
+		let hash = match cid_str.find("/ipfs/") {
+			Some(index) => &cid_str[index + "/ipfs/".len()..],
+			None => cid_str,
+		};
+		let mut inspection = cid.inspect();
+		inspection.cid = hash.to_string();
+		inspection.base = match cid.version() {
+			Version::V0 => Some(Base::Base58Btc),
+			Version::V1 => {
+				let code = hash.chars().next().expect("the CID was parsed");
+				Some(Base::from_code(code)?)
+			}
+		};
+		Ok(inspection)
+	}
+}
+
+impl<const S: usize> Cid<S> {
+	/// Returns a breakdown of the CID into its parts.
+	///
+	/// The returned value implements `Display`, which renders a summary similar
+	/// to `ipfs cid inspect`. As a CID doesn't know the multibase it was parsed
+	/// from, the multibase is unknown, parse the string into an [`Inspection`]
+	/// instead to get it.
+	pub fn inspect(&self) -> Inspection {
+		let name = |code| Multicodec::from_code(code).map(|codec| codec.name());
+		let v0 = match self.version() {
+			Version::V0 => Some(*self),
+			Version::V1 => Self::new(Version::V0, self.codec(), *self.hash()).ok(),
+		};
+		Inspection {
+			cid: self.to_string(),
+			base: None,
+			version: self.version(),
+			codec: self.codec(),
+			codec_name: name(self.codec()),
+			hash_code: self.hash().code(),
+			hash_name: name(self.hash().code()),
+			digest: self.hash().digest().to_vec(),
+			v0: v0.map(|cid| cid.to_string()),
+			v1: Self::new_v1(self.codec(), *self.hash()).to_string(),
+		}
+	}
+}
+
+fn version_name(version: Version) -> &'static str {
+	match version {
+		Version::V0 => "cidv0",
+		Version::V1 => "cidv1",
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, crate::cid::Cid as Cid64, alloc::format};
+
+	const V0: &str = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n";
+	const V1: &str =
+		"bafybeihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku";
+
+	#[test]
+	fn test_inspect() {
+		let cid = Cid64::try_from(V0).unwrap();
+		let inspection = cid.inspect();
+		assert_eq!(inspection.base, None);
+		assert_eq!(inspection.version, Version::V0);
+		assert_eq!(inspection.codec_name, Some("dag-pb"));
+		assert_eq!(inspection.hash_name, Some("sha2-256"));
+		assert_eq!(inspection.digest.len(), 32);
+		assert_eq!(inspection.v0.as_deref(), Some(V0));
+		assert_eq!(inspection.v1, V1);
+		assert_eq!(
+			inspection.human_readable(),
+			format!(
+				"base58btc - cidv0 - dag-pb - (sha2-256 : 256 : {})",
+				Base::Base16Upper.encode(cid.hash().digest())
+			)
+		);
+	}
+
+	#[test]
+	fn test_inspect_str() {
+		let base36 = Cid64::try_from(V1)
+			.unwrap()
+			.to_string_of_base(Base::Base36Lower)
+			.unwrap();
+		let inspection: Inspection = base36.parse().unwrap();
+		assert_eq!(inspection.cid, base36);
+		assert_eq!(inspection.base, Some(Base::Base36Lower));
+		assert_eq!(inspection.v0.as_deref(), Some(V0));
+		assert!(inspection.human_readable().starts_with("base36 - cidv1 - "));
+
+		let inspection: Inspection = format!("/ipfs/{}", V0).parse().unwrap();
+		assert_eq!(inspection.cid, V0);
+		assert_eq!(inspection.base, Some(Base::Base58Btc));
+
+		assert!("not a cid".parse::<Inspection>().is_err());
+	}
+
+	#[test]
+	fn test_inspect_display() {
+		let cid = Cid64::try_from(
+			"bafyreibjo4xmgaevkgud7mbifn3dzp4v4lyaui4yvqp3f2bqwtxcjrdqg4",
+		)
+		.unwrap();
+		let display = cid.inspect().to_string();
+		let lines: Vec<_> = display.lines().collect();
+		assert_eq!(lines.len(), 10);
+		assert_eq!(lines[1], "Multibase:      unknown");
+		assert_eq!(lines[3], "Codec:          dag-cbor (0x71)");
+		assert_eq!(lines[4], "Multihash:      sha2-256 (0x12)");
+		assert_eq!(lines[7], "CIDv0:          not possible");
+		assert_eq!(lines[8], format!("CIDv1:          {}", cid));
+	}
+}
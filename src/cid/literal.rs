@@ -0,0 +1,102 @@
+//! Support for the [`cid!`](crate::cid!) macro.
+
+use {
+	super::{cid::Cid, version::Version},
+	crate::multibase::literal as multibase,
+};
+
+/// Parses a CID string, in a const context.
+pub const fn parse<const S: usize>(
+	input: &str,
+) -> Result<Cid<S>, &'static str> {
+	let bytes = input.as_bytes();
+	// CIDv0 is base58btc encoded without a multibase prefix.
+	if bytes.len() == 46 && bytes[0] == b'Q' && bytes[1] == b'm' {
+		return match multibase::decode_base_x(bytes, multibase::BASE58_BTC) {
+			Ok(decoded) => Cid::from_bytes_const(decoded.as_bytes()),
+			Err(err) => Err(err),
+		};
+	}
+	let decoded = match multibase::decode(input) {
+		Ok(decoded) => decoded,
+		Err(err) => return Err(err),
+	};
+	match Cid::from_bytes_const(decoded.as_bytes()) {
+		Ok(cid) if matches!(cid.version(), Version::V0) => {
+			Err("CIDv0 cannot have a multibase prefix")
+		}
+		result => result,
+	}
+}
+
+/// Creates a CID from a string at compile time.
+///
+/// CIDv1 needs to be encoded with base16, base32, base36 or base58btc, a
+/// malformed CID fails the build. The allocated digest size defaults to 64
+/// bytes, another one can be passed as second argument.
+///
+/// ```
+/// use ipld_nostd::{cid, Cid};
+///
+/// const ROOT: Cid = cid!("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n");
+/// ```
+#[macro_export]
+macro_rules! cid {
+	($cid:expr) => {
+		$crate::cid!($cid, 64)
+	};
+	($cid:expr, $size:expr) => {{
+		const CID: $crate::cid::CidGeneric<$size> =
+			match $crate::cid::literal::parse::<$size>($cid) {
+				Ok(cid) => cid,
+				Err(err) => ::core::panic!("{}", err),
+			};
+		CID
+	}};
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::parse, crate::cid::CidGeneric};
+
+	const V0: &str = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n";
+	const V1: &str =
+		"bafyreibjo4xmgaevkgud7mbifn3dzp4v4lyaui4yvqp3f2bqwtxcjrdqg4";
+
+	const CID_V0: CidGeneric<32> =
+		cid!("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n", 32);
+	const CID_V1: CidGeneric<64> =
+		cid!("bafyreibjo4xmgaevkgud7mbifn3dzp4v4lyaui4yvqp3f2bqwtxcjrdqg4");
+
+	#[test]
+	fn test_cid_literal() {
+		assert_eq!(CID_V0, CidGeneric::<32>::try_from(V0).unwrap());
+		assert_eq!(CID_V1, CidGeneric::<64>::try_from(V1).unwrap());
+
+		let v1 = CidGeneric::<64>::try_from(V1).unwrap();
+		for base in [
+			crate::multibase::Base::Base16Upper,
+			crate::multibase::Base::Base32Upper,
+			crate::multibase::Base::Base36Lower,
+			crate::multibase::Base::Base58Btc,
+		] {
+			let string = v1.to_string_of_base(base).unwrap();
+			assert_eq!(parse::<64>(&string), Ok(v1));
+		}
+	}
+
+	#[test]
+	fn test_cid_literal_invalid() {
+		assert!(parse::<64>("").is_err());
+		assert!(parse::<64>(&V1[..V1.len() - 1]).is_err());
+		assert!(parse::<16>(V1).is_err());
+		assert!(
+			parse::<64>("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR10").is_err()
+		);
+		assert!(parse::<64>(&V0[..V0.len() - 1]).is_err());
+		// CIDv0 with a multibase prefix.
+		assert!(
+			parse::<64>("zQmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n").is_err()
+		);
+	}
+}
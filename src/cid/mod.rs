@@ -2,14 +2,24 @@
 //!
 //! Implementation of [cid](https://github.com/ipld/cid) in Rust.
 
+mod borrowed;
 mod cid;
 mod error;
+mod inline;
+mod inspect;
+#[doc(hidden)]
+pub mod literal;
+mod path;
 pub mod serde;
 mod version;
 
 pub use self::{
-	cid::Cid as CidGeneric,
+	borrowed::CidRef,
+	cid::{Cid as CidGeneric, CidBytes},
 	error::{Error, Result},
+	inline::InlinePolicy,
+	inspect::Inspection,
+	path::{ContentPath, Namespace, PathError, Root},
 	version::Version,
 };
 
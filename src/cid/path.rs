@@ -0,0 +1,542 @@
+//! IPFS and IPNS paths, native URLs and gateway URLs.
+//!
+//! The same content can be addressed in several ways:
+//!
+//! - a content path, e.g. `/ipfs/<cid>/a/b` or `/ipns/<name>/a/b`
+//! - a native URL, e.g. `ipfs://<cid>/a/b`
+//! - a path gateway URL, e.g. `https://example.com/ipfs/<cid>/a/b`
+//! - a subdomain gateway URL, e.g. `https://<cid>.ipfs.example.com/a/b`
+//!
+//! All of them parse into a [`ContentPath`], which can be formatted back into
+//! each form. The remainder of the path can be resolved within a DAG-CBOR block
+//! with [`crate::dag::path::get`].
+
+use {
+	super::{error::Error, version::Version, Cid},
+	crate::multibase::{self, Base},
+	alloc::{
+		format,
+		string::{String, ToString},
+		vec::Vec,
+	},
+	core::{fmt, str::FromStr},
+	core2::error::Error as StdError,
+};
+
+/// The namespace of a content path.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Namespace {
+	/// Immutable content, addressed by a CID.
+	Ipfs,
+	/// Mutable content, addressed by a key or a DNSLink name.
+	Ipns,
+}
+
+impl Namespace {
+	/// Returns the name of the namespace, e.g. `ipfs`.
+	pub const fn as_str(&self) -> &'static str {
+		match self {
+			Self::Ipfs => "ipfs",
+			Self::Ipns => "ipns",
+		}
+	}
+
+	fn from_label(label: &str) -> Option<Self> {
+		match label {
+			"ipfs" => Some(Self::Ipfs),
+			"ipns" => Some(Self::Ipns),
+			_ => None,
+		}
+	}
+}
+
+impl fmt::Display for Namespace {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+/// The root of a content path.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Root {
+	/// A CID, which is always the case for IPFS paths.
+	Cid(Cid),
+	/// A DNSLink name of an IPNS path, e.g. `docs.ipfs.tech`.
+	Name(String),
+}
+
+impl fmt::Display for Root {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Cid(cid) => write!(f, "{}", cid),
+			Self::Name(name) => f.write_str(name),
+		}
+	}
+}
+
+/// Error when parsing or formatting a [`ContentPath`].
+#[derive(Debug)]
+pub enum PathError {
+	/// The namespace is neither `ipfs` nor `ipns`.
+	UnknownNamespace,
+	/// The CID or name after the namespace is missing.
+	MissingRoot,
+	/// The root of an IPFS path isn't a valid CID.
+	InvalidCid(Error),
+	/// The root of an IPNS path is neither a CID nor a DNS name.
+	InvalidName,
+	/// A CID in a subdomain needs a case-insensitive multibase, as hostnames
+	/// are case-insensitive.
+	CaseSensitiveBase(Base),
+	/// The CID or name doesn't fit into a single DNS label of 63 characters.
+	LabelTooLong,
+}
+
+impl fmt::Display for PathError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::UnknownNamespace => write!(f, "Expected an ipfs or ipns namespace"),
+			Self::MissingRoot => write!(f, "Missing CID or name after the namespace"),
+			Self::InvalidCid(err) => write!(f, "Invalid CID: {}", err),
+			Self::InvalidName => write!(f, "Invalid IPNS name"),
+			Self::CaseSensitiveBase(base) => {
+				write!(f, "Multibase {} can't be used in a subdomain", base.name())
+			}
+			Self::LabelTooLong => write!(f, "Subdomain label is too long"),
+		}
+	}
+}
+
+impl StdError for PathError {}
+
+impl From<Error> for PathError {
+	fn from(err: Error) -> Self {
+		Self::InvalidCid(err)
+	}
+}
+
+/// A parsed IPFS or IPNS path, see the [module documentation](self).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ContentPath {
+	namespace: Namespace,
+	root: Root,
+	path: String,
+}
+
+impl ContentPath {
+	/// Creates an IPFS path to the CID.
+	pub fn ipfs(cid: Cid) -> Self {
+		Self {
+			namespace: Namespace::Ipfs,
+			root: Root::Cid(cid),
+			path: String::new(),
+		}
+	}
+
+	/// Creates an IPNS path to the root.
+	pub fn ipns(root: Root) -> Self {
+		Self {
+			namespace: Namespace::Ipns,
+			root,
+			path: String::new(),
+		}
+	}
+
+	/// Returns the same content path with the given path remainder.
+	pub fn with_path(mut self, path: &str) -> Self {
+		self.path = normalize(path);
+		self
+	}
+
+	/// Returns the namespace.
+	pub const fn namespace(&self) -> Namespace {
+		self.namespace
+	}
+
+	/// Returns the root.
+	pub const fn root(&self) -> &Root {
+		&self.root
+	}
+
+	/// Returns the CID of the root, if it is one.
+	pub const fn cid(&self) -> Option<&Cid> {
+		match &self.root {
+			Root::Cid(cid) => Some(cid),
+			Root::Name(_) => None,
+		}
+	}
+
+	/// Returns the remainder of the path after the root, without leading or
+	/// trailing slashes, e.g. `a/b`.
+	///
+	/// It's empty if the path points to the root.
+	pub fn path(&self) -> &str {
+		&self.path
+	}
+
+	/// Upgrades a CIDv0 root to CIDv1, other roots are returned unchanged.
+	pub fn into_v1(mut self) -> Result<Self, PathError> {
+		if let Root::Cid(cid) = self.root {
+			self.root = Root::Cid(cid.into_v1()?);
+		}
+		Ok(self)
+	}
+
+	/// Parses a content path, a native URL or a gateway URL.
+	///
+	/// The CID is kept as it is, use [`ContentPath::into_v1`] to upgrade a
+	/// CIDv0. Query strings and fragments of URLs are ignored.
+	pub fn parse(input: &str) -> Result<Self, PathError> {
+		// Content path, e.g. `/ipfs/<cid>/a`.
+		if let Some(path) = input.strip_prefix('/') {
+			let (namespace, rest) = split_segment(path);
+			let namespace =
+				Namespace::from_label(namespace).ok_or(PathError::UnknownNamespace)?;
+			return Self::from_parts(namespace, rest);
+		}
+
+		let (scheme, rest) = match input.split_once("://") {
+			Some((scheme, rest)) => (Some(scheme), rest),
+			None => (None, input),
+		};
+		let rest = strip_query(rest);
+
+		// Native URL, e.g. `ipfs://<cid>/a`.
+		if let Some(namespace) = scheme.and_then(Namespace::from_label) {
+			return Self::from_parts(namespace, rest);
+		}
+
+		let (host, path) = split_segment(rest);
+		// Subdomain gateway, e.g. `<cid>.ipfs.example.com/a`. Hosts such as
+		// `docs.ipfs.tech` are ordinary ones, their first label isn't a root.
+		let mut labels = host.splitn(3, '.');
+		if let (Some(label), Some(namespace)) = (labels.next(), labels.next()) {
+			if let Some(namespace) = Namespace::from_label(namespace) {
+				if let Some(root) = parse_label(namespace, label) {
+					return Ok(Self {
+						namespace,
+						root: root?,
+						path: normalize(path),
+					});
+				}
+			}
+		}
+		// Path gateway, e.g. `example.com/ipfs/<cid>/a`.
+		if scheme.is_some() {
+			let (namespace, rest) = split_segment(path);
+			let namespace =
+				Namespace::from_label(namespace).ok_or(PathError::UnknownNamespace)?;
+			return Self::from_parts(namespace, rest);
+		}
+		Err(PathError::UnknownNamespace)
+	}
+
+	/// Parses the part after the namespace, e.g. `<cid>/a/b`.
+	fn from_parts(namespace: Namespace, input: &str) -> Result<Self, PathError> {
+		let (root, path) = split_segment(strip_query(input));
+		if root.is_empty() {
+			return Err(PathError::MissingRoot);
+		}
+		let root = match namespace {
+			Namespace::Ipfs => Root::Cid(parse_cid(root)?),
+			Namespace::Ipns => match parse_cid(root) {
+				Ok(cid) => Root::Cid(cid),
+				Err(_) if is_dns_name(root) => Root::Name(root.to_string()),
+				Err(_) => return Err(PathError::InvalidName),
+			},
+		};
+		Ok(Self {
+			namespace,
+			root,
+			path: normalize(path),
+		})
+	}
+
+	/// Returns the content path, e.g. `/ipfs/<cid>/a/b`.
+	pub fn to_path_string(&self) -> String {
+		self.to_string()
+	}
+
+	/// Returns the native URL, e.g. `ipfs://<cid>/a/b`.
+	pub fn to_native_url(&self) -> String {
+		format!("{}://{}{}", self.namespace, self.root, self.path_suffix())
+	}
+
+	/// Returns the URL of the path gateway, e.g.
+	/// `https://example.com/ipfs/<cid>/a/b`.
+	pub fn to_path_gateway_url(&self, gateway: &str) -> String {
+		format!("https://{}{}", gateway.trim_end_matches('/'), self)
+	}
+
+	/// Returns the URL of the subdomain gateway, e.g.
+	/// `https://<cid>.ipfs.example.com/a/b`.
+	///
+	/// CIDs are encoded as CIDv1 with base32, or base36 for IPNS keys, as
+	/// hostnames are case-insensitive. This fails if the result doesn't fit
+	/// into a DNS label.
+	pub fn to_subdomain_url(&self, gateway: &str) -> Result<String, PathError> {
+		let label = match (&self.root, self.namespace) {
+			(Root::Cid(cid), Namespace::Ipfs) => cid.into_v1()?.to_string(),
+			(Root::Cid(cid), Namespace::Ipns) => {
+				cid.into_v1()?.to_string_of_base(Base::Base36Lower)?
+			}
+			// Dots and dashes are escaped, so that the name is a single label.
+			(Root::Name(name), _) => name.replace('-', "--").replace('.', "-"),
+		};
+		if label.len() > 63 {
+			return Err(PathError::LabelTooLong);
+		}
+		Ok(format!(
+			"https://{}.{}.{}{}",
+			label,
+			self.namespace,
+			gateway.trim_end_matches('/'),
+			self.path_suffix()
+		))
+	}
+
+	fn path_suffix(&self) -> String {
+		if self.path.is_empty() {
+			String::new()
+		} else {
+			format!("/{}", self.path)
+		}
+	}
+}
+
+impl fmt::Display for ContentPath {
+	/// Formats the content path, e.g. `/ipfs/<cid>/a/b`.
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "/{}/{}{}", self.namespace, self.root, self.path_suffix())
+	}
+}
+
+impl FromStr for ContentPath {
+	type Err = PathError;
+
+	fn from_str(input: &str) -> Result<Self, Self::Err> {
+		Self::parse(input)
+	}
+}
+
+/// Splits off the first segment of a path.
+fn split_segment(input: &str) -> (&str, &str) {
+	input.split_once('/').unwrap_or((input, ""))
+}
+
+/// Removes the query string and the fragment of a URL.
+fn strip_query(input: &str) -> &str {
+	match input.find(['?', '#']) {
+		Some(index) => &input[..index],
+		None => input,
+	}
+}
+
+/// Removes empty segments, so that there are no leading, trailing or double
+/// slashes.
+fn normalize(path: &str) -> String {
+	let segments: Vec<_> = path.split('/').filter(|s| !s.is_empty()).collect();
+	segments.join("/")
+}
+
+fn parse_cid(input: &str) -> Result<Cid, PathError> {
+	Ok(Cid::try_from(input)?)
+}
+
+/// Parses the root of a subdomain, which is a CID or, for IPNS, an escaped
+/// DNSLink name.
+///
+/// Returns `None` if the label is neither, so that the host isn't a subdomain
+/// gateway.
+fn parse_label(
+	namespace: Namespace,
+	label: &str,
+) -> Option<Result<Root, PathError>> {
+	if let Ok(cid) = parse_cid(label) {
+		return Some(check_case_insensitive(label, &cid).map(|()| Root::Cid(cid)));
+	}
+	match namespace {
+		Namespace::Ipfs => None,
+		Namespace::Ipns => {
+			let name = unescape_dns_label(label);
+			is_dns_name(&name).then_some(Ok(Root::Name(name)))
+		}
+	}
+}
+
+/// Checks that a CID from a subdomain survives the lowercasing of hostnames.
+fn check_case_insensitive(label: &str, cid: &Cid) -> Result<(), PathError> {
+	if cid.version() == Version::V0 {
+		return Err(PathError::CaseSensitiveBase(Base::Base58Btc));
+	}
+	let (base, _) = multibase::decode(label).map_err(Error::from)?;
+	match base {
+		Base::Base32Lower | Base::Base36Lower | Base::Base16Lower => Ok(()),
+		base => Err(PathError::CaseSensitiveBase(base)),
+	}
+}
+
+/// Reverts the escaping of a DNSLink name in a subdomain, where dots are
+/// replaced by dashes and dashes are doubled.
+fn unescape_dns_label(label: &str) -> String {
+	let mut name = String::with_capacity(label.len());
+	let mut chars = label.chars().peekable();
+	while let Some(char) = chars.next() {
+		if char != '-' {
+			name.push(char);
+		} else if chars.peek() == Some(&'-') {
+			chars.next();
+			name.push('-');
+		} else {
+			name.push('.');
+		}
+	}
+	name
+}
+
+fn is_dns_name(name: &str) -> bool {
+	name.contains('.')
+		&& name.split('.').all(|label| {
+			!label.is_empty()
+				&& label.len() <= 63
+				&& label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+		})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const V0: &str = "QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n";
+	const V1: &str =
+		"bafybeihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku";
+
+	fn v1() -> Cid {
+		Cid::try_from(V1).unwrap()
+	}
+
+	#[test]
+	fn test_parse_forms() {
+		let expected = ContentPath::ipfs(v1()).with_path("a/b");
+		for input in [
+			format!("/ipfs/{}/a/b", V1),
+			format!("/ipfs/{}/a//b/", V1),
+			format!("ipfs://{}/a/b", V1),
+			format!("ipfs://{}/a/b?filename=x#top", V1),
+			format!("https://example.com/ipfs/{}/a/b", V1),
+			format!("https://{}.ipfs.example.com/a/b", V1),
+			format!("{}.ipfs.dweb.link/a/b", V1),
+			format!("https://gateway.ipfs.io/ipfs/{}/a/b", V1),
+		] {
+			assert_eq!(ContentPath::parse(&input).unwrap(), expected, "{}", input);
+		}
+
+		let root = ContentPath::parse(&format!("/ipfs/{}", V1)).unwrap();
+		assert_eq!(root.path(), "");
+		assert_eq!(root.cid(), Some(&v1()));
+	}
+
+	#[test]
+	fn test_parse_v0() {
+		let path: ContentPath = format!("/ipfs/{}/x", V0).parse().unwrap();
+		assert_eq!(path.cid().unwrap().version(), Version::V0);
+		assert_eq!(path.to_string(), format!("/ipfs/{}/x", V0));
+		let upgraded = path.into_v1().unwrap();
+		assert_eq!(upgraded, ContentPath::ipfs(v1()).with_path("x"));
+	}
+
+	#[test]
+	fn test_parse_ipns() {
+		let path = ContentPath::parse("/ipns/docs.ipfs.tech/install").unwrap();
+		assert_eq!(path.namespace(), Namespace::Ipns);
+		assert_eq!(path.root(), &Root::Name("docs.ipfs.tech".into()));
+		assert_eq!(path.path(), "install");
+		assert_eq!(
+			path.to_subdomain_url("dweb.link").unwrap(),
+			"https://docs-ipfs-tech.ipns.dweb.link/install"
+		);
+
+		let subdomain =
+			ContentPath::parse("https://en-wikipedia--on--ipfs-org.ipns.dweb.link/")
+				.unwrap();
+		assert_eq!(
+			subdomain.root(),
+			&Root::Name("en.wikipedia-on-ipfs.org".into())
+		);
+
+		// Gateways whose hosts look like subdomains.
+		let path =
+			ContentPath::parse(&format!("https://docs.ipfs.tech/ipfs/{}", V1))
+				.unwrap();
+		assert_eq!(path, ContentPath::ipfs(v1()));
+		let path =
+			ContentPath::parse("https://gateway.ipns.io/ipns/docs.ipfs.tech/a")
+				.unwrap();
+		assert_eq!(path.root(), &Root::Name("docs.ipfs.tech".into()));
+
+		let key = ContentPath::ipns(Root::Cid(v1()));
+		let url = key.to_subdomain_url("dweb.link").unwrap();
+		assert!(url.starts_with("https://k"));
+		assert_eq!(ContentPath::parse(&url).unwrap(), key);
+	}
+
+	#[test]
+	fn test_format() {
+		let path = ContentPath::ipfs(Cid::try_from(V0).unwrap()).with_path("/a/b");
+		assert_eq!(path.to_path_string(), format!("/ipfs/{}/a/b", V0));
+		assert_eq!(path.to_native_url(), format!("ipfs://{}/a/b", V0));
+		assert_eq!(
+			path.to_path_gateway_url("example.com/"),
+			format!("https://example.com/ipfs/{}/a/b", V0)
+		);
+		// CIDv0 is upgraded for subdomains.
+		assert_eq!(
+			path.to_subdomain_url("example.com").unwrap(),
+			format!("https://{}.ipfs.example.com/a/b", V1)
+		);
+		for url in [
+			path.to_path_string(),
+			path.to_native_url(),
+			path.to_path_gateway_url("example.com"),
+		] {
+			assert_eq!(ContentPath::parse(&url).unwrap(), path);
+		}
+	}
+
+	#[test]
+	fn test_parse_errors() {
+		assert!(matches!(
+			ContentPath::parse("/ipld/x"),
+			Err(PathError::UnknownNamespace)
+		));
+		assert!(matches!(
+			ContentPath::parse("/ipfs/"),
+			Err(PathError::MissingRoot)
+		));
+		assert!(matches!(
+			ContentPath::parse("/ipfs/notacid/a"),
+			Err(PathError::InvalidCid(_))
+		));
+		assert!(matches!(
+			ContentPath::parse("ipns://not a name"),
+			Err(PathError::InvalidName)
+		));
+		assert!(matches!(
+			ContentPath::parse("https://example.com/a/b"),
+			Err(PathError::UnknownNamespace)
+		));
+		let base58 = v1().to_string_of_base(Base::Base58Btc).unwrap();
+		assert!(matches!(
+			ContentPath::parse(&format!("{}.ipns.dweb.link", base58)),
+			Err(PathError::CaseSensitiveBase(Base::Base58Btc))
+		));
+		assert!(matches!(
+			ContentPath::parse(&format!("{}.ipfs.dweb.link", base58)),
+			Err(PathError::CaseSensitiveBase(Base::Base58Btc))
+		));
+		assert!(matches!(
+			ContentPath::parse(&format!("https://{}.ipfs.dweb.link", V0)),
+			Err(PathError::CaseSensitiveBase(Base::Base58Btc))
+		));
+	}
+}
@@ -3,13 +3,16 @@
 //! CIDs cannot directly be represented in any of the native Serde Data model
 //! types. In order to work around that limitation. a newtype struct is
 //! introduced, that is used as a marker for Serde (de)serialization.
+//!
+//! Human readable formats (e.g. `serde_json`) get the CID as its canonical
+//! string instead, deserializing accepts both forms.
 
 use {
 	super::CidGeneric,
 	alloc::{format, vec::Vec},
 	core::{convert::TryFrom, fmt},
 	serde::{de, ser},
-	serde_bytes::ByteBuf,
+	serde_bytes::Bytes,
 };
 
 /// An identifier that is used internally by Serde implementations that support
@@ -26,12 +29,19 @@ pub const CID_SERDE_PRIVATE_IDENTIFIER: &str =
 /// ```text
 /// struct $__private__serde__identifier__for__cid(serde_bytes::BytesBuf);
 /// ```
+///
+/// If the serializer is human readable, the CID is serialized as string.
 impl<const SIZE: usize> ser::Serialize for CidGeneric<SIZE> {
 	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
 	where
 		S: ser::Serializer,
 	{
-		let value = ByteBuf::from(self.to_bytes());
+		if serializer.is_human_readable() {
+			return serializer.collect_str(self);
+		}
+		// The bytes are kept on the stack, so that serializing doesn't allocate.
+		let bytes = self.to_cid_bytes();
+		let value = Bytes::new(bytes.as_bytes());
 		serializer.serialize_newtype_struct(CID_SERDE_PRIVATE_IDENTIFIER, &value)
 	}
 }
@@ -71,6 +81,50 @@ impl<'de, const SIZE: usize> de::Visitor<'de> for BytesToCidVisitor<SIZE> {
 	}
 }
 
+/// Visitor to transform a CID string, or any of the byte forms, into a CID.
+struct HumanReadableVisitor<const SIZE: usize>;
+
+impl<'de, const SIZE: usize> de::Visitor<'de> for HumanReadableVisitor<SIZE> {
+	type Value = CidGeneric<SIZE>;
+
+	fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "a valid CID as string or in bytes")
+	}
+
+	fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+	where
+		E: de::Error,
+	{
+		CidGeneric::<SIZE>::try_from(value).map_err(|err| {
+			de::Error::custom(format!("Failed to deserialize CID: {}", err))
+		})
+	}
+
+	fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+	where
+		E: de::Error,
+	{
+		BytesToCidVisitor.visit_bytes(value)
+	}
+
+	fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+	where
+		A: de::SeqAccess<'de>,
+	{
+		BytesToCidVisitor.visit_seq(seq)
+	}
+
+	fn visit_newtype_struct<D>(
+		self,
+		deserializer: D,
+	) -> Result<Self::Value, D::Error>
+	where
+		D: de::Deserializer<'de>,
+	{
+		deserializer.deserialize_any(self)
+	}
+}
+
 /// Deserialize a CID into a newtype struct.
 ///
 /// Deserialize a CID that was serialized as a newtype struct, so that can be
@@ -79,11 +133,17 @@ impl<'de, const SIZE: usize> de::Visitor<'de> for BytesToCidVisitor<SIZE> {
 /// ```text
 /// struct $__private__serde__identifier__for__cid(serde_bytes::BytesBuf);
 /// ```
+///
+/// If the deserializer is human readable, the CID may also be a string.
 impl<'de, const SIZE: usize> de::Deserialize<'de> for CidGeneric<SIZE> {
 	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
 	where
 		D: de::Deserializer<'de>,
 	{
+		if deserializer.is_human_readable() {
+			return deserializer.deserialize_any(HumanReadableVisitor);
+		}
+
 		/// Main visitor to deserialize a CID.
 		///
 		/// This visitor has only a single entry point to deserialize CIDs, it's
@@ -118,16 +178,44 @@ impl<'de, const SIZE: usize> de::Deserialize<'de> for CidGeneric<SIZE> {
 
 #[cfg(test)]
 mod tests {
-	use super::CidGeneric;
+	use {
+		super::{CidGeneric, CID_SERDE_PRIVATE_IDENTIFIER},
+		alloc::{boxed::Box, format},
+		serde_test::{assert_de_tokens, assert_tokens, Configure, Token},
+	};
+
+	const CID: &str =
+		"bafkreibme22gw2h7y2h7tg2fhqotaqjucnbc24deqo72b6mkl2egezxhvy";
 
 	#[test]
 	fn test_cid_serde() {
-		let cid = CidGeneric::<70>::try_from(
-			"bafkreibme22gw2h7y2h7tg2fhqotaqjucnbc24deqo72b6mkl2egezxhvy",
-		)
-		.unwrap();
-		let bytes = serde_json::to_string(&cid).unwrap();
-		let cid2 = serde_json::from_str(&bytes).unwrap();
+		let cid = CidGeneric::<70>::try_from(CID).unwrap();
+		let json = serde_json::to_string(&cid).unwrap();
+		assert_eq!(json, format!("\"{}\"", CID));
+		let cid2 = serde_json::from_str(&json).unwrap();
 		assert_eq!(cid, cid2);
+
+		// The bytes form is still accepted.
+		let bytes = serde_json::to_string(&cid.to_bytes()).unwrap();
+		let cid3: CidGeneric<70> = serde_json::from_str(&bytes).unwrap();
+		assert_eq!(cid, cid3);
+	}
+
+	#[test]
+	fn test_cid_serde_tokens() {
+		let cid = CidGeneric::<64>::try_from(CID).unwrap();
+		// Tokens need to be `'static`.
+		let bytes: &'static [u8] = Box::leak(cid.to_bytes().into_boxed_slice());
+		let newtype_bytes = [
+			Token::NewtypeStruct {
+				name: CID_SERDE_PRIVATE_IDENTIFIER,
+			},
+			Token::Bytes(bytes),
+		];
+
+		assert_tokens(&cid.readable(), &[Token::Str(CID)]);
+		assert_tokens(&cid.compact(), &newtype_bytes);
+		assert_de_tokens(&cid.readable(), &newtype_bytes);
+		assert_de_tokens(&cid.readable(), &[Token::Bytes(bytes)]);
 	}
 }
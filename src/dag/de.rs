@@ -4,10 +4,11 @@ use {
 	super::{
 		cbor4ii_nonpub::{marker, peek_one, pull_one},
 		error::DecodeError,
+		raw::{self, RAW_VALUE_SERDE_PRIVATE_IDENTIFIER},
 		CBOR_TAGS_CID,
 	},
 	crate::cid::serde::CID_SERDE_PRIVATE_IDENTIFIER,
-	alloc::borrow::Cow,
+	alloc::{borrow::Cow, vec::Vec},
 	cbor4ii::core::{
 		dec::{self, Decode},
 		major,
@@ -107,6 +108,56 @@ impl<'de, R: dec::Read<'de>> Deserializer<R> {
 		}
 	}
 
+	/// Captures the encoded bytes of the next value without decoding it.
+	///
+	/// The bytes are borrowed from the input if the reader allows it, else they
+	/// are copied.
+	fn deserialize_raw_value<V>(
+		&mut self,
+		visitor: V,
+	) -> Result<V::Value, DecodeError<R::Error>>
+	where
+		V: Visitor<'de>,
+	{
+		let mut scratch = Vec::new();
+		loop {
+			let chunk = match self.reader.fill(usize::MAX)? {
+				// The whole value is available with the lifetime of the input, hence
+				// it can be borrowed.
+				dec::Reference::Long(buf) if scratch.is_empty() => {
+					match raw::value_len(buf) {
+						Ok(len) => {
+							self.reader.advance(len);
+							return visitor.visit_borrowed_bytes(&buf[..len]);
+						}
+						Err(DecodeError::Eof) => buf,
+						Err(error) => return Err(error),
+					}
+				}
+				dec::Reference::Long(buf) => buf,
+				dec::Reference::Short(buf) => buf,
+			};
+			if chunk.is_empty() {
+				return Err(DecodeError::Eof);
+			}
+
+			// Collect the value until it's complete, without consuming more bytes
+			// from the reader than the value is long.
+			let chunk_len = chunk.len();
+			let start = scratch.len();
+			scratch.extend_from_slice(chunk);
+			match raw::value_len(&scratch) {
+				Ok(len) => {
+					self.reader.advance(len - start);
+					scratch.truncate(len);
+					return visitor.visit_byte_buf(scratch);
+				}
+				Err(DecodeError::Eof) => self.reader.advance(chunk_len),
+				Err(error) => return Err(error),
+			}
+		}
+	}
+
 	/// This method should be called after a value has been deserialized to ensure
 	/// there is no trailing data in the input source.
 	pub fn end(&mut self) -> Result<(), DecodeError<R::Error>> {
@@ -308,6 +359,8 @@ impl<'de, 'a, R: dec::Read<'de>> serde::Deserializer<'de>
 	{
 		if name == CID_SERDE_PRIVATE_IDENTIFIER {
 			self.deserialize_cid(visitor)
+		} else if name == RAW_VALUE_SERDE_PRIVATE_IDENTIFIER {
+			self.deserialize_raw_value(visitor)
 		} else {
 			visitor.visit_newtype_struct(self)
 		}
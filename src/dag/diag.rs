@@ -0,0 +1,584 @@
+//! CBOR diagnostic notation.
+//!
+//! Diagnostic notation is the human readable text form of CBOR, defined in
+//! [RFC 8949, Section 8]. It looks like JSON with a few additions, e.g. byte
+//! strings are written as `h'0102'` and tags as `42(...)`. It's useful for
+//! looking at encoded blocks and for writing test vectors by hand.
+//!
+//! ```
+//! use ipld_nostd::dag::diag;
+//!
+//! let encoded = diag::from_diagnostic(r#"{"b": [1, -2], "a": h'ff'}"#).unwrap();
+//! assert_eq!(encoded, b"\xa2aaA\xffab\x82\x01\x21");
+//! let diagnostic = diag::to_diagnostic(&encoded).unwrap();
+//! assert_eq!(diagnostic, r#"{"a": h'ff', "b": [1, -2]}"#);
+//! ```
+//!
+//! The parser produces DAG-CBOR, hence only CBOR that can be represented in
+//! DAG-CBOR is supported: the only tag is 42 (CID), map keys are strings and
+//! map entries end up in canonical order. CIDs can be written either as
+//! `42(h'00...')` or as `42("bafy...")`. Comments between slashes, like
+//! `/ this one /`, are ignored.
+//!
+//! [RFC 8949, Section 8]: https://www.rfc-editor.org/rfc/rfc8949#section-8
+
+use {
+	super::{
+		de::from_slice,
+		error::{CodecError, DecodeError},
+		raw,
+		ser::to_vec,
+	},
+	crate::{cid::Cid, ipld::Ipld, multibase::Base},
+	alloc::{
+		collections::BTreeMap,
+		format,
+		string::{String, ToString},
+		vec::Vec,
+	},
+	cbor4ii::core::major,
+	core::{convert::Infallible, fmt::Write},
+};
+
+/// How CIDs are rendered in diagnostic notation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CidFormat {
+	/// As tagged bytes, e.g. `42(h'0001...')`, which is the plain CBOR form.
+	#[default]
+	Bytes,
+	/// As tagged text in the default string form of the CID, e.g.
+	/// `42("bafy...")`.
+	String,
+	/// As tagged text in the given multibase encoding.
+	Base(Base),
+}
+
+/// Renders encoded DAG-CBOR as diagnostic notation.
+pub fn to_diagnostic(buf: &[u8]) -> Result<String, DecodeError<Infallible>> {
+	to_diagnostic_with(buf, CidFormat::Bytes)
+}
+
+/// Renders encoded DAG-CBOR as diagnostic notation, with CIDs in the given
+/// format.
+pub fn to_diagnostic_with(
+	buf: &[u8],
+	cid_format: CidFormat,
+) -> Result<String, DecodeError<Infallible>> {
+	// Checking the whole input upfront also limits the nesting depth.
+	if raw::value_len::<Infallible>(buf)? != buf.len() {
+		return Err(DecodeError::TrailingData);
+	}
+	let mut output = String::new();
+	print(buf, cid_format, &mut output)?;
+	Ok(output)
+}
+
+/// Parses diagnostic notation into encoded DAG-CBOR.
+pub fn from_diagnostic(diagnostic: &str) -> Result<Vec<u8>, CodecError> {
+	let mut parser = Parser {
+		input: diagnostic,
+		pos: 0,
+	};
+	let value = parser.value(raw::MAX_DEPTH)?;
+	parser.skip_whitespace()?;
+	if parser.pos != parser.input.len() {
+		return Err(parser.error("Trailing data").into());
+	}
+	Ok(to_vec(&value)?)
+}
+
+/// Prints the value at the start of `buf` and returns its encoded length.
+fn print(
+	buf: &[u8],
+	cid_format: CidFormat,
+	output: &mut String,
+) -> Result<usize, DecodeError<Infallible>> {
+	match buf.first().map(|byte| byte >> 5) {
+		Some(major::ARRAY) => {
+			let (len, mut pos) = raw::header(buf)?;
+			output.push('[');
+			for index in 0..len {
+				if index != 0 {
+					output.push_str(", ");
+				}
+				pos += print(&buf[pos..], cid_format, output)?;
+			}
+			output.push(']');
+			Ok(pos)
+		}
+		Some(major::MAP) => {
+			let (len, mut pos) = raw::header(buf)?;
+			output.push('{');
+			for index in 0..len {
+				if index != 0 {
+					output.push_str(", ");
+				}
+				pos += print(&buf[pos..], cid_format, output)?;
+				output.push_str(": ");
+				pos += print(&buf[pos..], cid_format, output)?;
+			}
+			output.push('}');
+			Ok(pos)
+		}
+		_ => {
+			let len = raw::value_len(buf)?;
+			let value: Ipld = from_slice(&buf[..len])?;
+			print_scalar(&value, cid_format, output)?;
+			Ok(len)
+		}
+	}
+}
+
+/// Prints a value that is neither a list nor a map.
+fn print_scalar(
+	value: &Ipld,
+	cid_format: CidFormat,
+	output: &mut String,
+) -> Result<(), DecodeError<Infallible>> {
+	match value {
+		Ipld::Null => output.push_str("null"),
+		Ipld::Bool(true) => output.push_str("true"),
+		Ipld::Bool(false) => output.push_str("false"),
+		Ipld::Integer(integer) => output.push_str(&integer.to_string()),
+		Ipld::Float(float) if float.is_nan() => output.push_str("NaN"),
+		Ipld::Float(float) if float.is_infinite() => {
+			if float.is_sign_negative() {
+				output.push('-');
+			}
+			output.push_str("Infinity");
+		}
+		// The debug representation always contains a decimal point or an
+		// exponent, so that floats are distinguishable from integers.
+		Ipld::Float(float) => output.push_str(&format!("{:?}", float)),
+		Ipld::String(string) => print_string(string, output),
+		Ipld::Bytes(bytes) => print_bytes(&[], bytes, output),
+		Ipld::Link(cid) => {
+			output.push_str("42(");
+			match cid_format {
+				CidFormat::Bytes => print_bytes(&[0], &cid.to_bytes(), output),
+				CidFormat::String => print_string(&cid.to_string(), output),
+				CidFormat::Base(base) => {
+					let string = cid
+						.to_string_of_base(base)
+						.map_err(|err| DecodeError::Msg(err.to_string()))?;
+					print_string(&string, output);
+				}
+			}
+			output.push(')');
+		}
+		Ipld::List(_) | Ipld::Map(_) => unreachable!("collections are printed"),
+	}
+	Ok(())
+}
+
+/// Prints a byte string, the bytes are the concatenation of `prefix` and
+/// `bytes`.
+fn print_bytes(prefix: &[u8], bytes: &[u8], output: &mut String) {
+	output.push_str("h'");
+	for byte in prefix.iter().chain(bytes) {
+		// Writing into a string cannot fail.
+		let _ = write!(output, "{:02x}", byte);
+	}
+	output.push('\'');
+}
+
+/// Prints a text string with JSON escaping.
+fn print_string(string: &str, output: &mut String) {
+	output.push('"');
+	for c in string.chars() {
+		match c {
+			'"' => output.push_str("\\\""),
+			'\\' => output.push_str("\\\\"),
+			'\n' => output.push_str("\\n"),
+			'\r' => output.push_str("\\r"),
+			'\t' => output.push_str("\\t"),
+			c if c.is_control() => {
+				let _ = write!(output, "\\u{:04x}", c as u32);
+			}
+			c => output.push(c),
+		}
+	}
+	output.push('"');
+}
+
+/// A recursive descent parser for diagnostic notation.
+struct Parser<'a> {
+	input: &'a str,
+	pos: usize,
+}
+
+impl Parser<'_> {
+	fn error(&self, message: &str) -> DecodeError<Infallible> {
+		DecodeError::Msg(format!("{} at position {}", message, self.pos))
+	}
+
+	fn peek(&self) -> Option<u8> {
+		self.input.as_bytes().get(self.pos).copied()
+	}
+
+	/// Consumes `token` if the remaining input starts with it.
+	fn eat(&mut self, token: &str) -> bool {
+		let found = self.input[self.pos..].starts_with(token);
+		if found {
+			self.pos += token.len();
+		}
+		found
+	}
+
+	fn expect(&mut self, token: &str) -> Result<(), DecodeError<Infallible>> {
+		self.skip_whitespace()?;
+		if self.eat(token) {
+			Ok(())
+		} else {
+			Err(self.error(&format!("Expected `{}`", token)))
+		}
+	}
+
+	/// Skips whitespace and comments.
+	fn skip_whitespace(&mut self) -> Result<(), DecodeError<Infallible>> {
+		loop {
+			match self.peek() {
+				Some(b' ' | b'\t' | b'\n' | b'\r') => self.pos += 1,
+				Some(b'/') => match self.input[self.pos + 1..].find('/') {
+					Some(len) => self.pos += len + 2,
+					None => return Err(self.error("Unterminated comment")),
+				},
+				_ => return Ok(()),
+			}
+		}
+	}
+
+	fn value(&mut self, depth: usize) -> Result<Ipld, DecodeError<Infallible>> {
+		let depth = depth.checked_sub(1).ok_or(DecodeError::DepthLimit)?;
+		self.skip_whitespace()?;
+		if self.eat("[") {
+			self.list(depth)
+		} else if self.eat("{") {
+			self.map(depth)
+		} else if self.eat("h'") {
+			self.bytes().map(Ipld::Bytes)
+		} else if self.peek() == Some(b'"') {
+			self.string().map(Ipld::String)
+		} else if self.eat("null") {
+			Ok(Ipld::Null)
+		} else if self.eat("true") {
+			Ok(Ipld::Bool(true))
+		} else if self.eat("false") {
+			Ok(Ipld::Bool(false))
+		} else if self.eat("NaN") {
+			Ok(Ipld::Float(f64::NAN))
+		} else if self.eat("Infinity") {
+			Ok(Ipld::Float(f64::INFINITY))
+		} else if self.eat("-Infinity") {
+			Ok(Ipld::Float(f64::NEG_INFINITY))
+		} else if matches!(self.peek(), Some(b'-' | b'0'..=b'9')) {
+			self.number(depth)
+		} else {
+			Err(self.error("Unexpected input"))
+		}
+	}
+
+	fn list(&mut self, depth: usize) -> Result<Ipld, DecodeError<Infallible>> {
+		let mut list = Vec::new();
+		self.skip_whitespace()?;
+		if self.eat("]") {
+			return Ok(Ipld::List(list));
+		}
+		loop {
+			list.push(self.value(depth)?);
+			self.skip_whitespace()?;
+			if !self.eat(",") {
+				self.expect("]")?;
+				return Ok(Ipld::List(list));
+			}
+		}
+	}
+
+	fn map(&mut self, depth: usize) -> Result<Ipld, DecodeError<Infallible>> {
+		let mut map = BTreeMap::new();
+		self.skip_whitespace()?;
+		if self.eat("}") {
+			return Ok(Ipld::Map(map));
+		}
+		loop {
+			self.skip_whitespace()?;
+			let key_pos = self.pos;
+			if self.peek() != Some(b'"') {
+				return Err(self.error("Map keys must be strings"));
+			}
+			let key = self.string()?;
+			self.expect(":")?;
+			let value = self.value(depth)?;
+			if map.insert(key, value).is_some() {
+				self.pos = key_pos;
+				return Err(self.error("Duplicate map key"));
+			}
+			self.skip_whitespace()?;
+			if !self.eat(",") {
+				self.expect("}")?;
+				return Ok(Ipld::Map(map));
+			}
+		}
+	}
+
+	/// Parses the content of a hex encoded byte string, after the `h'`.
+	fn bytes(&mut self) -> Result<Vec<u8>, DecodeError<Infallible>> {
+		let mut bytes = Vec::new();
+		let mut high = None;
+		loop {
+			let byte = self
+				.peek()
+				.ok_or_else(|| self.error("Unterminated byte string"))?;
+			let nibble = match byte {
+				b'\'' if high.is_none() => {
+					self.pos += 1;
+					return Ok(bytes);
+				}
+				b' ' | b'\t' | b'\n' | b'\r' => {
+					self.pos += 1;
+					continue;
+				}
+				b'0'..=b'9' => byte - b'0',
+				b'a'..=b'f' => byte - b'a' + 10,
+				b'A'..=b'F' => byte - b'A' + 10,
+				_ => return Err(self.error("Invalid hex digit")),
+			};
+			match high.take() {
+				Some(high) => bytes.push(high << 4 | nibble),
+				None => high = Some(nibble),
+			}
+			self.pos += 1;
+		}
+	}
+
+	/// Parses a JSON style text string.
+	fn string(&mut self) -> Result<String, DecodeError<Infallible>> {
+		let mut string = String::new();
+		self.pos += 1;
+		loop {
+			let c = self.input[self.pos..]
+				.chars()
+				.next()
+				.ok_or_else(|| self.error("Unterminated string"))?;
+			self.pos += c.len_utf8();
+			match c {
+				'"' => return Ok(string),
+				'\\' => {
+					let escape = self.peek();
+					self.pos += 1;
+					let escaped = match escape {
+						Some(b'"') => '"',
+						Some(b'\\') => '\\',
+						Some(b'/') => '/',
+						Some(b'b') => '\u{8}',
+						Some(b'f') => '\u{c}',
+						Some(b'n') => '\n',
+						Some(b'r') => '\r',
+						Some(b't') => '\t',
+						Some(b'u') => self.unicode_escape()?,
+						_ => {
+							self.pos -= 1;
+							return Err(self.error("Invalid escape sequence"));
+						}
+					};
+					string.push(escaped);
+				}
+				c => string.push(c),
+			}
+		}
+	}
+
+	/// Parses the four hex digits of a `\u` escape, and the second half of a
+	/// surrogate pair if needed.
+	fn unicode_escape(&mut self) -> Result<char, DecodeError<Infallible>> {
+		let first = self.hex4()?;
+		let code = if (0xd800..0xdc00).contains(&first) {
+			if !self.eat("\\u") {
+				return Err(self.error("Missing low surrogate"));
+			}
+			let second = self.hex4()?;
+			if !(0xdc00..0xe000).contains(&second) {
+				return Err(self.error("Invalid low surrogate"));
+			}
+			0x10000 + ((first - 0xd800) << 10) + (second - 0xdc00)
+		} else {
+			first
+		};
+		char::from_u32(code).ok_or_else(|| self.error("Invalid unicode escape"))
+	}
+
+	fn hex4(&mut self) -> Result<u32, DecodeError<Infallible>> {
+		let digits = self
+			.input
+			.get(self.pos..self.pos + 4)
+			.filter(|digits| digits.bytes().all(|byte| byte.is_ascii_hexdigit()))
+			.ok_or_else(|| self.error("Invalid unicode escape"))?;
+		self.pos += 4;
+		Ok(u32::from_str_radix(digits, 16).expect("digits were checked"))
+	}
+
+	/// Parses an integer, a float or a tag.
+	fn number(&mut self, depth: usize) -> Result<Ipld, DecodeError<Infallible>> {
+		let start = self.pos;
+		self.pos += 1;
+		while let Some(b'0'..=b'9' | b'.' | b'e' | b'E' | b'+' | b'-') = self.peek()
+		{
+			self.pos += 1;
+		}
+		let number = &self.input[start..self.pos];
+		if number.contains(['.', 'e', 'E']) {
+			return number.parse().map(Ipld::Float).map_err(|_| {
+				self.pos = start;
+				self.error("Invalid float")
+			});
+		}
+		let integer: i128 = number.parse().map_err(|_| {
+			self.pos = start;
+			self.error("Invalid integer")
+		})?;
+		if self.peek() == Some(b'(') {
+			self.pos += 1;
+			if integer != 42 {
+				self.pos = start;
+				return Err(self.error("Only tag 42 (CID) is supported"));
+			}
+			let cid = self.cid(depth)?;
+			self.expect(")")?;
+			return Ok(Ipld::Link(cid));
+		}
+		if !(-(u64::MAX as i128 + 1)..=u64::MAX as i128).contains(&integer) {
+			self.pos = start;
+			return Err(self.error("Integer out of range"));
+		}
+		Ok(Ipld::Integer(integer))
+	}
+
+	/// Parses the content of tag 42, either as bytes or as string.
+	fn cid(&mut self, depth: usize) -> Result<Cid, DecodeError<Infallible>> {
+		self.skip_whitespace()?;
+		let start = self.pos;
+		let cid = match self.value(depth)? {
+			Ipld::Bytes(bytes) => match bytes.split_first() {
+				// CBOR encoded CIDs have a zero byte prefix.
+				Some((0, bytes)) => Cid::try_from(bytes).ok(),
+				_ => None,
+			},
+			Ipld::String(string) => Cid::try_from(string).ok(),
+			_ => None,
+		};
+		cid.ok_or_else(|| {
+			self.pos = start;
+			self.error("Invalid CID")
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, crate::dag::to_vec, alloc::vec};
+
+	const CID: &str =
+		"bafyreibjo4xmgaevkgud7mbifn3dzp4v4lyaui4yvqp3f2bqwtxcjrdqg4";
+
+	#[test]
+	fn test_to_diagnostic() {
+		let cid = Cid::try_from(CID).unwrap();
+		let ipld = Ipld::Map(BTreeMap::from([
+			("link".into(), Ipld::Link(cid)),
+			(
+				"list".into(),
+				Ipld::List(vec![
+					Ipld::Integer(-(u64::MAX as i128) - 1),
+					Ipld::Float(1.0),
+					Ipld::Null,
+					Ipld::Bool(true),
+				]),
+			),
+			("s".into(), Ipld::String("a\"\n\u{1}ü".into())),
+			("e".into(), Ipld::Map(BTreeMap::new())),
+		]));
+		let encoded = to_vec(&ipld).unwrap();
+
+		let cid_hex: String = cid
+			.to_bytes()
+			.iter()
+			.map(|byte| format!("{:02x}", byte))
+			.collect();
+		assert_eq!(
+			to_diagnostic(&encoded).unwrap(),
+			format!(
+				"{{\"e\": {{}}, \"s\": \"a\\\"\\n\\u0001ü\", \"link\": 42(h'00{}'), \
+				 \"list\": [-18446744073709551616, 1.0, null, true]}}",
+				cid_hex
+			)
+		);
+		assert!(to_diagnostic_with(&encoded, CidFormat::String)
+			.unwrap()
+			.contains(&format!("\"link\": 42(\"{}\")", CID)));
+		assert!(
+			to_diagnostic_with(&encoded, CidFormat::Base(Base::Base58Btc))
+				.unwrap()
+				.contains("\"link\": 42(\"zdpu")
+		);
+	}
+
+	#[test]
+	fn test_to_diagnostic_invalid() {
+		assert!(to_diagnostic(b"\x82\x01").is_err());
+		assert!(to_diagnostic(b"\x01\x02").is_err());
+		assert!(to_diagnostic(b"").is_err());
+	}
+
+	#[test]
+	fn test_roundtrip() {
+		let diagnostic = format!(
+			"{{\"a\": [1, -2, 3.5, h'00ff', \"\\u00fc\\ud83d\\ude00\"], \"bb\": \
+			 42(\"{}\"), \"c\": {{\"d\": false}}}}",
+			CID
+		);
+		let encoded = from_diagnostic(&diagnostic).unwrap();
+		let printed = to_diagnostic_with(&encoded, CidFormat::String).unwrap();
+		assert_eq!(
+			printed,
+			format!(
+				"{{\"a\": [1, -2, 3.5, h'00ff', \"ü😀\"], \"c\": {{\"d\": false}}, \
+				 \"bb\": 42(\"{}\")}}",
+				CID
+			)
+		);
+		assert_eq!(from_diagnostic(&printed).unwrap(), encoded);
+
+		let bytes = to_diagnostic(&encoded).unwrap();
+		assert_eq!(from_diagnostic(&bytes).unwrap(), encoded);
+	}
+
+	#[test]
+	fn test_from_diagnostic_whitespace_and_comments() {
+		let encoded = from_diagnostic(
+			"/ a list / [\n\t1 , h'01 02' / two bytes /,\n\t{ \"a\" : null }\n]",
+		)
+		.unwrap();
+		assert_eq!(encoded, b"\x83\x01\x42\x01\x02\xa1\x61a\xf6");
+	}
+
+	#[test]
+	fn test_from_diagnostic_invalid() {
+		for invalid in [
+			"",
+			"[1, 2",
+			"[1 2]",
+			"{1: 2}",
+			"{\"a\": 1, \"a\": 2}",
+			"h'123'",
+			"\"abc",
+			"1(2)",
+			"42(h'01')",
+			"18446744073709551616",
+			"NaN",
+			"1 2",
+			"/ comment",
+		] {
+			assert!(from_diagnostic(invalid).is_err(), "{}", invalid);
+		}
+	}
+}
@@ -48,6 +48,18 @@ impl<E: fmt::Debug> From<cbor4ii::EncodeError<E>> for EncodeError<E> {
 	}
 }
 
+/// The buffer that is serialized into is too small.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BufferFull;
+
+impl ser::StdError for BufferFull {}
+
+impl fmt::Display for BufferFull {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("buffer is full")
+	}
+}
+
 /// A decoding error.
 #[derive(Debug)]
 pub enum DecodeError<E> {
@@ -117,13 +117,17 @@
 mod cbor4ii_nonpub;
 pub mod codec;
 pub mod de;
+pub mod diag;
 pub mod error;
+pub mod path;
+pub mod raw;
 pub mod ser;
 
 pub use {
 	de::from_slice,
-	error::{DecodeError, EncodeError},
-	ser::to_vec,
+	error::{BufferFull, DecodeError, EncodeError},
+	raw::{to_raw_value, RawValue},
+	ser::{encoded_len, to_slice, to_vec},
 };
 
 /// The CBOR tag that is used for CIDs.
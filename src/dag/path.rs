@@ -0,0 +1,350 @@
+//! Lazy extraction of values from encoded DAG-CBOR.
+//!
+//! Decoding a whole block just to read a single field of it is wasteful. The
+//! functions in this module follow an IPLD path through the encoded data and
+//! only decode the value it points to. The data is walked with the
+//! [`Deserializer`] until the value is found, the values before it are skipped
+//! over, the values after it aren't looked at. Use [`get_range_validated`] to
+//! check that the whole data is a single well-formed value.
+//!
+//! A path is a list of segments separated by `/`, e.g. `header/height`. A
+//! segment is either a map key or the index of a list element. Links are not
+//! followed, as the linked block is not part of the data.
+//!
+//! ```
+//! use ipld_nostd::{dag, ipld::Ipld};
+//!
+//! // {"header": {"height": 7, "parents": []}, "data": [1, 2]}
+//! let block = b"\xa2ddata\x82\x01\x02fheader\xa2fheight\x07gparents\x80";
+//!
+//! let height: u64 = dag::path::get(block, "header/height").unwrap().unwrap();
+//! assert_eq!(height, 7);
+//! let second: Ipld = dag::path::get(block, "/data/1").unwrap().unwrap();
+//! assert_eq!(second, Ipld::Integer(2));
+//! assert_eq!(dag::path::get_range(block, "data").unwrap(), Some(6..9));
+//! ```
+
+use {
+	super::{
+		de::{from_slice, Deserializer},
+		error::DecodeError,
+		RawValue,
+	},
+	core::{convert::Infallible, fmt, ops::Range, str::Split},
+	serde::de::{
+		self,
+		Deserialize,
+		DeserializeSeed,
+		IgnoredAny,
+		MapAccess,
+		SeqAccess,
+		Visitor,
+	},
+};
+
+/// Decodes the value at the given path.
+///
+/// Returns `None` if the path does not exist in the data. `T` may be any type
+/// that can be decoded from DAG-CBOR, e.g. [`crate::ipld::Ipld`] for untyped
+/// access, or [`super::RawValue`] to get the encoded value.
+pub fn get<'a, T>(
+	buf: &'a [u8],
+	path: &str,
+) -> Result<Option<T>, DecodeError<Infallible>>
+where
+	T: Deserialize<'a>,
+{
+	match get_range(buf, path)? {
+		Some(range) => from_slice(&buf[range]).map(Some),
+		None => Ok(None),
+	}
+}
+
+/// Returns the position of the encoded value at the given path.
+///
+/// Returns `None` if the path does not exist in the data. The walk stops at the
+/// returned value, the data after it isn't checked.
+pub fn get_range(
+	buf: &[u8],
+	path: &str,
+) -> Result<Option<Range<usize>>, DecodeError<Infallible>> {
+	walk(buf, path, false)
+}
+
+/// Returns the position of the encoded value at the given path, like
+/// [`get_range`], but also checks that the whole data is a single well-formed
+/// value.
+pub fn get_range_validated(
+	buf: &[u8],
+	path: &str,
+) -> Result<Option<Range<usize>>, DecodeError<Infallible>> {
+	walk(buf, path, true)
+}
+
+fn walk(
+	buf: &[u8],
+	path: &str,
+	validate: bool,
+) -> Result<Option<Range<usize>>, DecodeError<Infallible>> {
+	let mut deserializer = Deserializer::from_slice(buf);
+	let seed = PathSeed {
+		segments: path.split('/'),
+		validate,
+	};
+	let value = seed.deserialize(&mut deserializer)?;
+	if validate {
+		deserializer.end()?;
+	}
+	Ok(value.map(|value| {
+		let start = value.as_bytes().as_ptr() as usize - buf.as_ptr() as usize;
+		start..start + value.as_bytes().len()
+	}))
+}
+
+/// Follows the remaining segments of a path. The value it points to is
+/// borrowed from the input as a [`RawValue`].
+#[derive(Clone)]
+struct PathSeed<'p> {
+	segments: Split<'p, char>,
+	/// Whether the values after the one at the path are walked too.
+	validate: bool,
+}
+
+impl<'de, 'p> DeserializeSeed<'de> for PathSeed<'p> {
+	type Value = Option<&'de RawValue>;
+
+	fn deserialize<D>(mut self, deserializer: D) -> Result<Self::Value, D::Error>
+	where
+		D: de::Deserializer<'de>,
+	{
+		match self.segments.find(|segment| !segment.is_empty()) {
+			Some(segment) => deserializer.deserialize_any(PathVisitor {
+				segment,
+				rest: self,
+			}),
+			None => Deserialize::deserialize(deserializer).map(Some),
+		}
+	}
+}
+
+/// Looks up a path segment in a map or a list. All other values don't have
+/// children, they are skipped.
+struct PathVisitor<'p> {
+	segment: &'p str,
+	rest: PathSeed<'p>,
+}
+
+impl<'de, 'p> Visitor<'de> for PathVisitor<'p> {
+	type Value = Option<&'de RawValue>;
+
+	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str("any DAG-CBOR value")
+	}
+
+	fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+	where
+		A: MapAccess<'de>,
+	{
+		let mut found = None;
+		while let Some(key) = map.next_key::<&str>()? {
+			if key == self.segment {
+				found = map.next_value_seed(self.rest.clone())?;
+				if !self.rest.validate {
+					break;
+				}
+			} else {
+				map.next_value::<IgnoredAny>()?;
+			}
+		}
+		Ok(found)
+	}
+
+	fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+	where
+		A: SeqAccess<'de>,
+	{
+		let index = self.segment.parse::<usize>().ok();
+		let mut found = None;
+		for i in 0.. {
+			if Some(i) == index {
+				match seq.next_element_seed(self.rest.clone())? {
+					Some(value) if !self.rest.validate => return Ok(value),
+					Some(value) => found = value,
+					None => break,
+				}
+			} else if seq.next_element::<IgnoredAny>()?.is_none() {
+				break;
+			}
+		}
+		Ok(found)
+	}
+
+	fn visit_newtype_struct<D>(
+		self,
+		deserializer: D,
+	) -> Result<Self::Value, D::Error>
+	where
+		D: de::Deserializer<'de>,
+	{
+		// Links are not followed, the linked block is not part of the data.
+		deserializer.deserialize_bytes(IgnoredAny)?;
+		Ok(None)
+	}
+
+	fn visit_bool<E>(self, _: bool) -> Result<Self::Value, E> {
+		Ok(None)
+	}
+
+	fn visit_i64<E>(self, _: i64) -> Result<Self::Value, E> {
+		Ok(None)
+	}
+
+	fn visit_i128<E>(self, _: i128) -> Result<Self::Value, E> {
+		Ok(None)
+	}
+
+	fn visit_u64<E>(self, _: u64) -> Result<Self::Value, E> {
+		Ok(None)
+	}
+
+	fn visit_u128<E>(self, _: u128) -> Result<Self::Value, E> {
+		Ok(None)
+	}
+
+	fn visit_f64<E>(self, _: f64) -> Result<Self::Value, E> {
+		Ok(None)
+	}
+
+	fn visit_str<E>(self, _: &str) -> Result<Self::Value, E> {
+		Ok(None)
+	}
+
+	fn visit_bytes<E>(self, _: &[u8]) -> Result<Self::Value, E> {
+		Ok(None)
+	}
+
+	fn visit_none<E>(self) -> Result<Self::Value, E> {
+		Ok(None)
+	}
+
+	fn visit_unit<E>(self) -> Result<Self::Value, E> {
+		Ok(None)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::*,
+		crate::{cid::Cid, dag::to_vec, ipld::Ipld},
+		alloc::{collections::BTreeMap, string::String, vec},
+		serde_derive::Deserialize,
+	};
+
+	fn block() -> Ipld {
+		let cid = Cid::try_from(
+			"bafyreibjo4xmgaevkgud7mbifn3dzp4v4lyaui4yvqp3f2bqwtxcjrdqg4",
+		)
+		.unwrap();
+		Ipld::Map(BTreeMap::from([
+			(
+				"header".into(),
+				Ipld::Map(BTreeMap::from([
+					("height".into(), Ipld::Integer(1234)),
+					("parent".into(), Ipld::Link(cid)),
+					("miner".into(), Ipld::String("me".into())),
+				])),
+			),
+			(
+				"messages".into(),
+				Ipld::List(vec![
+					Ipld::Bytes(vec![1, 2, 3]),
+					Ipld::Map(BTreeMap::from([(
+						"to".into(),
+						Ipld::String("you".into()),
+					)])),
+				]),
+			),
+		]))
+	}
+
+	#[derive(Debug, Deserialize, PartialEq)]
+	struct Message {
+		to: String,
+	}
+
+	#[test]
+	fn test_get() {
+		let ipld = block();
+		let encoded = to_vec(&ipld).unwrap();
+
+		let height: u32 = get(&encoded, "header/height").unwrap().unwrap();
+		assert_eq!(height, 1234);
+		let message: Message = get(&encoded, "/messages/1/").unwrap().unwrap();
+		assert_eq!(message, Message { to: "you".into() });
+		let header: Ipld = get(&encoded, "header").unwrap().unwrap();
+		assert_eq!(&header, ipld.get("header").unwrap().unwrap());
+		let whole: Ipld = get(&encoded, "").unwrap().unwrap();
+		assert_eq!(whole, ipld);
+	}
+
+	#[test]
+	fn test_get_missing() {
+		let encoded = to_vec(&block()).unwrap();
+
+		for path in [
+			"footer",
+			"header/heigh",
+			"header/height/0",
+			"messages/2",
+			"messages/to",
+			"header/parent/0",
+		] {
+			assert!(get::<Ipld>(&encoded, path).unwrap().is_none(), "{}", path);
+		}
+	}
+
+	#[test]
+	fn test_get_range() {
+		let ipld = block();
+		let encoded = to_vec(&ipld).unwrap();
+
+		let range = get_range(&encoded, "messages/0").unwrap().unwrap();
+		assert_eq!(&encoded[range.clone()], b"\x43\x01\x02\x03");
+		assert_eq!(
+			get_range_validated(&encoded, "messages/0").unwrap(),
+			Some(range.clone())
+		);
+		let range = get_range(&encoded, "header").unwrap().unwrap();
+		assert_eq!(
+			encoded[range].to_vec(),
+			to_vec(ipld.get("header").unwrap().unwrap()).unwrap()
+		);
+	}
+
+	#[test]
+	fn test_get_malformed() {
+		let mut encoded = to_vec(&block()).unwrap();
+
+		// The values after the one at the path are only checked on request.
+		let truncated = &encoded[..encoded.len() - 1];
+		assert!(get_range(truncated, "header/height").unwrap().is_some());
+		assert!(get_range_validated(truncated, "header/height").is_err());
+		assert!(get_range(truncated, "messages/1").is_err());
+
+		encoded.push(0x01);
+		assert!(get_range(&encoded, "header/height").unwrap().is_some());
+		assert!(matches!(
+			get_range_validated(&encoded, "header/height"),
+			Err(DecodeError::TrailingData)
+		));
+		assert!(matches!(
+			get_range_validated(&encoded, "footer"),
+			Err(DecodeError::TrailingData)
+		));
+		assert!(get::<Ipld>(&encoded, "footer").unwrap().is_none());
+
+		// Map keys must be strings.
+		assert!(get_range(b"\xa1\x01\x02", "1").is_err());
+	}
+}
@@ -0,0 +1,516 @@
+//! Raw, not yet decoded DAG-CBOR values.
+//!
+//! A [`RawValue`] captures the exact encoded bytes of a DAG-CBOR subtree. It
+//! can be used as a field of a deserialized struct to defer (or skip) the
+//! decoding of that part of the data, and it is written back verbatim when
+//! serialized with [`super::ser::Serializer`]. This keeps the encoded form
+//! byte-exact, which is needed e.g. when forwarding signed payloads.
+//!
+//! ```
+//! use ipld_nostd::dag::{self, RawValue};
+//!
+//! let encoded = b"\x82\x01\x82\x02\x03";
+//! let (_, payload): (u8, &RawValue) = dag::from_slice(encoded).unwrap();
+//! assert_eq!(payload.as_bytes(), b"\x82\x02\x03");
+//! assert_eq!(dag::to_vec(&(1, payload)).unwrap(), encoded);
+//! ```
+
+use {
+	super::{
+		cbor4ii_nonpub::marker,
+		error::{DecodeError, EncodeError},
+		CBOR_TAGS_CID,
+	},
+	alloc::{
+		borrow::ToOwned,
+		boxed::Box,
+		collections::TryReserveError,
+		vec::Vec,
+	},
+	cbor4ii::core::major,
+	core::{
+		convert::{Infallible, TryFrom},
+		fmt,
+	},
+	serde::{de, ser},
+};
+
+/// An identifier that is used internally by Serde implementations that support
+/// [`RawValue`]s.
+pub const RAW_VALUE_SERDE_PRIVATE_IDENTIFIER: &str =
+	"$__private__serde__identifier__for__raw_value";
+
+/// The maximum nesting depth of a raw value, it matches the recursion limit of
+/// the deserializer.
+pub(crate) const MAX_DEPTH: usize = 256;
+
+/// A single encoded DAG-CBOR value.
+///
+/// This type is unsized, it's used either as `&RawValue` borrowed from the
+/// input, or as `Box<RawValue>` when the input cannot be borrowed from.
+///
+/// When deserialized from DAG-CBOR it contains the exact bytes of the value
+/// without them being decoded. The bytes are only checked to be a single
+/// well-formed DAG-CBOR data item. When serialized to DAG-CBOR those bytes are
+/// written as they are.
+///
+/// Other Serde data formats see a `RawValue` as a newtype struct wrapping
+/// bytes.
+#[derive(PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct RawValue {
+	bytes: [u8],
+}
+
+#[allow(unsafe_code)]
+impl RawValue {
+	fn from_borrowed(bytes: &[u8]) -> &Self {
+		// SAFETY: `RawValue` is a transparent wrapper around `[u8]`.
+		unsafe { &*(bytes as *const [u8] as *const Self) }
+	}
+
+	fn from_owned(bytes: Box<[u8]>) -> Box<Self> {
+		// SAFETY: `RawValue` is a transparent wrapper around `[u8]`.
+		unsafe { Box::from_raw(Box::into_raw(bytes) as *mut Self) }
+	}
+
+	fn into_owned(raw_value: Box<Self>) -> Box<[u8]> {
+		// SAFETY: `RawValue` is a transparent wrapper around `[u8]`.
+		unsafe { Box::from_raw(Box::into_raw(raw_value) as *mut [u8]) }
+	}
+}
+
+impl RawValue {
+	/// Wraps the given bytes, after checking that they are exactly one
+	/// DAG-CBOR encoded value.
+	pub fn from_slice(bytes: &[u8]) -> Result<&Self, DecodeError<Infallible>> {
+		validate(bytes)?;
+		Ok(Self::from_borrowed(bytes))
+	}
+
+	/// Wraps the given bytes, after checking that they are exactly one
+	/// DAG-CBOR encoded value.
+	pub fn from_vec(
+		bytes: Vec<u8>,
+	) -> Result<Box<Self>, DecodeError<Infallible>> {
+		validate(&bytes)?;
+		Ok(Self::from_owned(bytes.into_boxed_slice()))
+	}
+
+	/// Returns the encoded bytes of the value.
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.bytes
+	}
+}
+
+impl Clone for Box<RawValue> {
+	fn clone(&self) -> Self {
+		(**self).to_owned()
+	}
+}
+
+impl ToOwned for RawValue {
+	type Owned = Box<RawValue>;
+
+	fn to_owned(&self) -> Self::Owned {
+		RawValue::from_owned(self.bytes.into())
+	}
+}
+
+impl AsRef<[u8]> for RawValue {
+	fn as_ref(&self) -> &[u8] {
+		&self.bytes
+	}
+}
+
+impl From<Box<RawValue>> for Box<[u8]> {
+	fn from(raw_value: Box<RawValue>) -> Self {
+		RawValue::into_owned(raw_value)
+	}
+}
+
+impl From<Box<RawValue>> for Vec<u8> {
+	fn from(raw_value: Box<RawValue>) -> Self {
+		RawValue::into_owned(raw_value).into_vec()
+	}
+}
+
+impl fmt::Debug for RawValue {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_tuple("RawValue").field(&&self.bytes).finish()
+	}
+}
+
+/// Serializes a value into a boxed [`RawValue`].
+pub fn to_raw_value<T>(
+	value: &T,
+) -> Result<Box<RawValue>, EncodeError<TryReserveError>>
+where
+	T: ser::Serialize + ?Sized,
+{
+	let bytes = super::to_vec(value)?;
+	Ok(RawValue::from_owned(bytes.into_boxed_slice()))
+}
+
+impl ser::Serialize for RawValue {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: ser::Serializer,
+	{
+		serializer.serialize_newtype_struct(
+			RAW_VALUE_SERDE_PRIVATE_IDENTIFIER,
+			serde_bytes::Bytes::new(&self.bytes),
+		)
+	}
+}
+
+/// Visitor to transform bytes into a [`RawValue`].
+struct RawValueVisitor<T>(core::marker::PhantomData<T>);
+
+impl<'de> de::Visitor<'de> for RawValueVisitor<&'de RawValue> {
+	type Value = &'de RawValue;
+
+	fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "a borrowed DAG-CBOR encoded value")
+	}
+
+	fn visit_newtype_struct<D>(
+		self,
+		deserializer: D,
+	) -> Result<Self::Value, D::Error>
+	where
+		D: de::Deserializer<'de>,
+	{
+		deserializer.deserialize_bytes(self)
+	}
+
+	fn visit_borrowed_bytes<E>(self, value: &'de [u8]) -> Result<Self::Value, E>
+	where
+		E: de::Error,
+	{
+		RawValue::from_slice(value).map_err(de::Error::custom)
+	}
+}
+
+impl<'de> de::Visitor<'de> for RawValueVisitor<Box<RawValue>> {
+	type Value = Box<RawValue>;
+
+	fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "a DAG-CBOR encoded value")
+	}
+
+	fn visit_newtype_struct<D>(
+		self,
+		deserializer: D,
+	) -> Result<Self::Value, D::Error>
+	where
+		D: de::Deserializer<'de>,
+	{
+		deserializer.deserialize_byte_buf(self)
+	}
+
+	fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+	where
+		E: de::Error,
+	{
+		self.visit_byte_buf(value.to_vec())
+	}
+
+	fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<Self::Value, E>
+	where
+		E: de::Error,
+	{
+		RawValue::from_vec(value).map_err(de::Error::custom)
+	}
+}
+
+impl<'de: 'a, 'a> de::Deserialize<'de> for &'a RawValue {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: de::Deserializer<'de>,
+	{
+		deserializer.deserialize_newtype_struct(
+			RAW_VALUE_SERDE_PRIVATE_IDENTIFIER,
+			RawValueVisitor::<&'de RawValue>(core::marker::PhantomData),
+		)
+	}
+}
+
+impl<'de> de::Deserialize<'de> for Box<RawValue> {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: de::Deserializer<'de>,
+	{
+		deserializer.deserialize_newtype_struct(
+			RAW_VALUE_SERDE_PRIVATE_IDENTIFIER,
+			RawValueVisitor::<Box<RawValue>>(core::marker::PhantomData),
+		)
+	}
+}
+
+/// Checks that `buf` contains exactly one DAG-CBOR encoded value.
+fn validate<E>(buf: &[u8]) -> Result<(), DecodeError<E>> {
+	if value_len(buf)? == buf.len() {
+		Ok(())
+	} else {
+		Err(DecodeError::TrailingData)
+	}
+}
+
+/// Returns the length of the DAG-CBOR encoded value at the start of `buf`.
+///
+/// The value is checked to be well-formed, but it is not decoded. Only the
+/// data items that the DAG-CBOR deserializer supports are accepted.
+pub(crate) fn value_len<E>(buf: &[u8]) -> Result<usize, DecodeError<E>> {
+	skip(buf, MAX_DEPTH)
+}
+
+/// Reads the header of the data item at the start of `buf`.
+///
+/// Returns the argument (a value or a length) and the size of the header.
+pub(crate) fn header<E>(buf: &[u8]) -> Result<(u64, usize), DecodeError<E>> {
+	let byte = *buf.first().ok_or(DecodeError::Eof)?;
+	let size = match byte & 0x1f {
+		info @ 0..=23 => return Ok((info.into(), 1)),
+		24 => 1,
+		25 => 2,
+		26 => 4,
+		27 => 8,
+		marker::START => return Err(DecodeError::IndefiniteSize),
+		_ => return Err(DecodeError::Unsupported { byte }),
+	};
+	let argument = buf.get(1..=size).ok_or(DecodeError::Eof)?;
+	let value = argument
+		.iter()
+		.fold(0u64, |value, byte| value << 8 | u64::from(*byte));
+	Ok((value, 1 + size))
+}
+
+/// Reads the header of a byte or text string and returns the start and the end
+/// position of its content.
+pub(crate) fn string_end<E>(
+	buf: &[u8],
+) -> Result<(usize, usize), DecodeError<E>> {
+	let (len, start) = header(buf)?;
+	let len = usize::try_from(len).map_err(DecodeError::CastOverflow)?;
+	let end = start
+		.checked_add(len)
+		.filter(|end| *end <= buf.len())
+		.ok_or(DecodeError::Eof)?;
+	Ok((start, end))
+}
+
+fn skip<E>(buf: &[u8], depth: usize) -> Result<usize, DecodeError<E>> {
+	let depth = depth.checked_sub(1).ok_or(DecodeError::DepthLimit)?;
+	let byte = *buf.first().ok_or(DecodeError::Eof)?;
+	match byte >> 5 {
+		major::UNSIGNED | major::NEGATIVE => Ok(header(buf)?.1),
+		major::BYTES => Ok(string_end(buf)?.1),
+		major::STRING => {
+			let (start, end) = string_end(buf)?;
+			core::str::from_utf8(&buf[start..end])
+				.map_err(DecodeError::InvalidUtf8)?;
+			Ok(end)
+		}
+		major::ARRAY => {
+			let (len, mut pos) = header(buf)?;
+			for _ in 0..len {
+				pos += skip(&buf[pos..], depth)?;
+			}
+			Ok(pos)
+		}
+		major::MAP => {
+			let (len, mut pos) = header(buf)?;
+			for _ in 0..len {
+				// Keys in DAG-CBOR are always strings.
+				let key = *buf.get(pos).ok_or(DecodeError::Eof)?;
+				if key >> 5 != major::STRING {
+					return Err(DecodeError::TypeMismatch {
+						name: "map key",
+						byte: key,
+					});
+				}
+				pos += skip(&buf[pos..], depth)?;
+				pos += skip(&buf[pos..], depth)?;
+			}
+			Ok(pos)
+		}
+		// The only supported tag is tag 42 (CID).
+		major::TAG => {
+			let (tag, pos) = header(buf)?;
+			if tag != CBOR_TAGS_CID {
+				return Err(DecodeError::TypeMismatch {
+					name: "CBOR tag",
+					byte: tag as u8,
+				});
+			}
+			let cid = *buf.get(pos).ok_or(DecodeError::Eof)?;
+			if cid >> 5 != major::BYTES {
+				return Err(DecodeError::Unsupported { byte: cid });
+			}
+			// CBOR encoded CIDs have a zero byte prefix.
+			let (start, end) = string_end(&buf[pos..])?;
+			if end - start <= 1 || buf[pos + start] != 0 {
+				return Err(DecodeError::Msg("Invalid CID".into()));
+			}
+			Ok(pos + end)
+		}
+		major::SIMPLE => {
+			let len = match byte {
+				marker::FALSE | marker::TRUE | marker::NULL => 1,
+				marker::F32 => 5,
+				marker::F64 => 9,
+				_ => return Err(DecodeError::Unsupported { byte }),
+			};
+			if buf.len() < len {
+				return Err(DecodeError::Eof);
+			}
+			Ok(len)
+		}
+		_ => unreachable!("the major type only has three bits"),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::*,
+		crate::{
+			cid::Cid,
+			dag::{from_slice, to_vec},
+			ipld::Ipld,
+		},
+		alloc::{string::String, vec},
+		serde_derive::{Deserialize, Serialize},
+	};
+
+	#[derive(Debug, Deserialize, Serialize)]
+	struct Borrowed<'a> {
+		name: String,
+		#[serde(borrow)]
+		payload: &'a RawValue,
+	}
+
+	#[derive(Debug, Deserialize, Serialize)]
+	struct Owned {
+		name: String,
+		payload: Box<RawValue>,
+	}
+
+	fn payload() -> Ipld {
+		let cid = Cid::try_from(
+			"bafyreibjo4xmgaevkgud7mbifn3dzp4v4lyaui4yvqp3f2bqwtxcjrdqg4",
+		)
+		.unwrap();
+		Ipld::List(vec![
+			Ipld::Integer(-3),
+			Ipld::String("hello".into()),
+			Ipld::Link(cid),
+			Ipld::Float(1.5),
+			Ipld::Null,
+		])
+	}
+
+	#[test]
+	fn test_raw_value_borrowed_roundtrip() {
+		let raw = to_raw_value(&payload()).unwrap();
+		let value = Borrowed {
+			name: "envelope".into(),
+			payload: &raw,
+		};
+		let encoded = to_vec(&value).unwrap();
+
+		let decoded: Borrowed = from_slice(&encoded).unwrap();
+		assert_eq!(decoded.payload, &*raw);
+		assert_eq!(
+			from_slice::<Ipld>(decoded.payload.as_bytes()).unwrap(),
+			payload()
+		);
+		assert_eq!(to_vec(&decoded).unwrap(), encoded);
+	}
+
+	#[test]
+	fn test_raw_value_owned_roundtrip() {
+		let encoded = to_vec(&Owned {
+			name: "envelope".into(),
+			payload: to_raw_value(&payload()).unwrap(),
+		})
+		.unwrap();
+
+		let decoded: Owned = from_slice(&encoded).unwrap();
+		assert_eq!(to_vec(&decoded).unwrap(), encoded);
+	}
+
+	/// A reader that only hands out a few bytes at a time, that cannot be
+	/// borrowed from.
+	struct ChunkedReader<'a>(&'a [u8]);
+
+	impl<'de> cbor4ii::core::dec::Read<'de> for ChunkedReader<'_> {
+		type Error = Infallible;
+
+		fn fill<'b>(
+			&'b mut self,
+			want: usize,
+		) -> Result<cbor4ii::core::dec::Reference<'de, 'b>, Self::Error> {
+			let len = self.0.len().min(want).min(3);
+			Ok(cbor4ii::core::dec::Reference::Short(&self.0[..len]))
+		}
+
+		fn advance(&mut self, n: usize) {
+			self.0 = &self.0[n.min(self.0.len())..];
+		}
+	}
+
+	#[test]
+	fn test_raw_value_from_reader() {
+		let raw = to_raw_value(&payload()).unwrap();
+		let encoded = to_vec(&(&*raw, 7)).unwrap();
+
+		let mut deserializer =
+			crate::dag::de::Deserializer::from_reader(ChunkedReader(&encoded));
+		let (decoded, number): (Box<RawValue>, u8) =
+			serde::Deserialize::deserialize(&mut deserializer).unwrap();
+		deserializer.end().unwrap();
+		assert_eq!(decoded, raw);
+		assert_eq!(number, 7);
+	}
+
+	#[test]
+	fn test_raw_value_is_verbatim() {
+		// A map with unsorted keys is written back as it is, instead of being
+		// re-encoded in canonical order.
+		let unsorted = b"\xa2bbb\x01aa\x02";
+		let raw = RawValue::from_slice(unsorted).unwrap();
+		assert_eq!(to_vec(&vec![raw]).unwrap(), b"\x81\xa2bbb\x01aa\x02");
+	}
+
+	#[test]
+	fn test_raw_value_invalid() {
+		// Trailing data.
+		assert!(matches!(
+			RawValue::from_slice(b"\x01\x02"),
+			Err(DecodeError::TrailingData)
+		));
+		// Truncated list.
+		assert!(matches!(
+			RawValue::from_slice(b"\x82\x01"),
+			Err(DecodeError::Eof)
+		));
+		// Indefinite length.
+		assert!(matches!(
+			RawValue::from_slice(b"\x9f\xff"),
+			Err(DecodeError::IndefiniteSize)
+		));
+		// Non-string map key.
+		assert!(matches!(
+			RawValue::from_slice(b"\xa1\x01\x02"),
+			Err(DecodeError::TypeMismatch { .. })
+		));
+		// Unsupported tag.
+		assert!(matches!(
+			RawValue::from_slice(b"\xc1\x01"),
+			Err(DecodeError::TypeMismatch { .. })
+		));
+		assert!(from_slice::<&RawValue>(b"\x82\x01").is_err());
+	}
+}
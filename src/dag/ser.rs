@@ -2,13 +2,19 @@
 
 pub use cbor4ii::core::utils::BufWriter;
 use {
-	super::{error::EncodeError, CBOR_TAGS_CID},
+	super::{
+		error::{BufferFull, EncodeError},
+		raw::{self, RAW_VALUE_SERDE_PRIVATE_IDENTIFIER},
+		CBOR_TAGS_CID,
+	},
 	crate::cid::serde::CID_SERDE_PRIVATE_IDENTIFIER,
 	alloc::{collections::TryReserveError, string::ToString, vec::Vec},
 	cbor4ii::core::{
 		enc::{self, Encode},
+		major,
 		types,
 	},
+	core::convert::Infallible,
 	serde::{ser, Serialize},
 };
 
@@ -189,7 +195,9 @@ impl<'a, W: enc::Write> serde::Serializer for &'a mut Serializer<W> {
 		value: &T,
 	) -> Result<Self::Ok, Self::Error> {
 		if name == CID_SERDE_PRIVATE_IDENTIFIER {
-			value.serialize(&mut CidSerializer(self))
+			value.serialize(&mut BytesSerializer(&mut self.writer, write_cid))
+		} else if name == RAW_VALUE_SERDE_PRIVATE_IDENTIFIER {
+			value.serialize(&mut BytesSerializer(&mut self.writer, write_raw_value))
 		} else {
 			value.serialize(self)
 		}
@@ -576,10 +584,41 @@ where
 	}
 }
 
-/// Serializing a CID correctly as DAG-CBOR.
-struct CidSerializer<'a, W>(&'a mut Serializer<W>);
+/// Writes the bytes of a CID correctly as DAG-CBOR.
+fn write_cid<W: enc::Write>(
+	writer: &mut W,
+	cid: &[u8],
+) -> Result<(), W::Error> {
+	// CIDs are serialized with CBOR tag 42.
+	let (tag, tag_len) = encode_header(major::TAG, CBOR_TAGS_CID);
+	// The bytes of the CID is prefixed with a null byte when encoded as CBOR.
+	// The headers are written by hand, so that no buffer is needed for
+	// prefixing the bytes.
+	let (header, header_len) = encode_header(major::BYTES, cid.len() as u64 + 1);
+	writer.push(&tag[..tag_len])?;
+	writer.push(&header[..header_len])?;
+	writer.push(&[0x00])?;
+	writer.push(cid)
+}
+
+/// Writes an already encoded DAG-CBOR value verbatim.
+fn write_raw_value<W: enc::Write>(
+	writer: &mut W,
+	value: &[u8],
+) -> Result<(), W::Error> {
+	// The bytes were checked to be a DAG-CBOR value when the `RawValue` was
+	// created, hence they are written as they are.
+	writer.push(value)
+}
+
+/// Serializing a newtype that only consists of bytes, like a CID or a raw
+/// value. The bytes are written to the writer by the given function.
+struct BytesSerializer<'a, W: enc::Write>(
+	&'a mut W,
+	fn(&mut W, &[u8]) -> Result<(), W::Error>,
+);
 
-impl<'a, W: enc::Write> ser::Serializer for &'a mut CidSerializer<'a, W>
+impl<'a, W: enc::Write> ser::Serializer for &'a mut BytesSerializer<'a, W>
 where
 	W::Error: core::fmt::Debug,
 {
@@ -646,11 +685,7 @@ where
 	}
 
 	fn serialize_bytes(self, value: &[u8]) -> Result<Self::Ok, Self::Error> {
-		// The bytes of the CID is prefixed with a null byte when encoded as CBOR.
-		let prefixed = [&[0x00], value].concat();
-		// CIDs are serialized with CBOR tag 42.
-		types::Tag(CBOR_TAGS_CID, types::Bytes(&prefixed[..]))
-			.encode(&mut self.0.writer)?;
+		(self.1)(self.0, value)?;
 		Ok(())
 	}
 
@@ -757,3 +792,1000 @@ where
 		Err(ser::Error::custom("unreachable"))
 	}
 }
+
+/// Serializes a value into the given buffer, without allocating.
+///
+/// Returns the number of bytes written. Map entries are sorted into the
+/// canonical order within the buffer itself, so no scratch memory is needed.
+/// The unused end of the buffer holds the positions of the entries while they
+/// are sorted. If there is no room left for them, the entries are parsed
+/// instead, which is slower for large maps.
+///
+/// The same values are accepted as by [`to_vec`] and [`encoded_len`].
+pub fn to_slice<T>(
+	buf: &mut [u8],
+	value: &T,
+) -> Result<usize, EncodeError<BufferFull>>
+where
+	T: Serialize + ?Sized,
+{
+	let mut serializer = InPlaceSerializer::new(SliceWriter::new(buf));
+	value.serialize(&mut serializer)?;
+	Ok(serializer.into_inner().pos)
+}
+
+/// Returns the length of the DAG-CBOR encoding of a value, without encoding it.
+///
+/// The returned length is exact, it's the length [`to_vec`] would return.
+pub fn encoded_len<T>(value: &T) -> Result<usize, EncodeError<Infallible>>
+where
+	T: Serialize + ?Sized,
+{
+	let mut serializer = InPlaceSerializer::new(LenCounter::default());
+	value.serialize(&mut serializer)?;
+	Ok(serializer.into_inner().0)
+}
+
+/// Encodes the header of a CBOR data item with the given major type and
+/// argument.
+///
+/// Returns a buffer and the number of bytes of it that are used.
+fn encode_header(major: u8, value: u64) -> ([u8; 9], usize) {
+	let mut buf = [0; 9];
+	let len = match value {
+		0..=0x17 => {
+			buf[0] = major << 5 | value as u8;
+			1
+		}
+		0x18..=0xff => {
+			buf[0] = major << 5 | 0x18;
+			buf[1] = value as u8;
+			2
+		}
+		0x100..=0xffff => {
+			buf[0] = major << 5 | 0x19;
+			buf[1..3].copy_from_slice(&(value as u16).to_be_bytes());
+			3
+		}
+		0x1_0000..=0xffff_ffff => {
+			buf[0] = major << 5 | 0x1a;
+			buf[1..5].copy_from_slice(&(value as u32).to_be_bytes());
+			5
+		}
+		_ => {
+			buf[0] = major << 5 | 0x1b;
+			buf[1..9].copy_from_slice(&value.to_be_bytes());
+			9
+		}
+	};
+	(buf, len)
+}
+
+/// A writer that allows to modify the data that was already written to it.
+///
+/// It's used for serializing without any scratch buffers. Headers of
+/// collections whose length isn't known beforehand are inserted once all
+/// elements were written, map entries are sorted once the whole map was
+/// written.
+trait InPlaceWrite: enc::Write {
+	/// Returns the number of bytes written so far.
+	fn position(&self) -> usize;
+
+	/// Inserts bytes at the given position, the data after it is moved.
+	fn insert(
+		&mut self,
+		position: usize,
+		bytes: &[u8],
+	) -> Result<(), Self::Error>;
+
+	/// Starts recording the ends of the entries of a new map. The returned
+	/// token is passed to [`InPlaceWrite::sort_map_entries`].
+	fn start_map(&self) -> Option<usize>;
+
+	/// Records that a map entry ends at the current position.
+	fn end_map_entry(&mut self);
+
+	/// Sorts the map entries that were written since the given position into
+	/// the canonical DAG-CBOR order.
+	fn sort_map_entries(
+		&mut self,
+		start: usize,
+		index: Option<usize>,
+	) -> Result<(), EncodeError<Self::Error>>;
+}
+
+/// A writer that only counts the number of bytes written to it.
+#[derive(Default)]
+struct LenCounter(usize);
+
+impl enc::Write for LenCounter {
+	type Error = Infallible;
+
+	#[inline]
+	fn push(&mut self, input: &[u8]) -> Result<(), Self::Error> {
+		self.0 += input.len();
+		Ok(())
+	}
+}
+
+impl InPlaceWrite for LenCounter {
+	#[inline]
+	fn position(&self) -> usize {
+		self.0
+	}
+
+	#[inline]
+	fn insert(
+		&mut self,
+		_position: usize,
+		bytes: &[u8],
+	) -> Result<(), Self::Error> {
+		self.0 += bytes.len();
+		Ok(())
+	}
+
+	#[inline]
+	fn start_map(&self) -> Option<usize> {
+		None
+	}
+
+	#[inline]
+	fn end_map_entry(&mut self) {}
+
+	#[inline]
+	fn sort_map_entries(
+		&mut self,
+		_start: usize,
+		_index: Option<usize>,
+	) -> Result<(), EncodeError<Self::Error>> {
+		// The order of the entries doesn't change the length.
+		Ok(())
+	}
+}
+
+/// The size of an entry of the map index of [`SliceWriter`].
+const INDEX_ENTRY_LEN: usize = core::mem::size_of::<usize>();
+
+/// A writer into a fixed buffer.
+///
+/// The unused end of the buffer holds an index of the ends of the map entries
+/// that were written, as a stack that grows downwards. Once the data reaches
+/// the index, it's discarded and maps are sorted by parsing their entries.
+struct SliceWriter<'a> {
+	buf: &'a mut [u8],
+	pos: usize,
+	/// The start of the map index, `None` once it was discarded.
+	index: Option<usize>,
+}
+
+impl<'a> SliceWriter<'a> {
+	fn new(buf: &'a mut [u8]) -> Self {
+		Self {
+			index: Some(buf.len()),
+			buf,
+			pos: 0,
+		}
+	}
+
+	/// Discards the map index if the data up to `end` would overwrite it.
+	fn reserve(&mut self, end: usize) -> Result<(), BufferFull> {
+		if end > self.buf.len() {
+			return Err(BufferFull);
+		}
+		if self.index.is_some_and(|index| end > index) {
+			self.index = None;
+		}
+		Ok(())
+	}
+
+	/// Returns the end of the `i`th entry of the map whose index starts at
+	/// `base`, `start` for entry `-1`.
+	fn entry_end(&self, start: usize, base: usize, i: Option<usize>) -> usize {
+		match i {
+			Some(i) => {
+				let at = base - (i + 1) * INDEX_ENTRY_LEN;
+				let mut bytes = [0; INDEX_ENTRY_LEN];
+				bytes.copy_from_slice(&self.buf[at..at + INDEX_ENTRY_LEN]);
+				usize::from_ne_bytes(bytes)
+			}
+			None => start,
+		}
+	}
+
+	fn set_entry_end(&mut self, base: usize, i: usize, end: usize) {
+		let at = base - (i + 1) * INDEX_ENTRY_LEN;
+		self.buf[at..at + INDEX_ENTRY_LEN].copy_from_slice(&end.to_ne_bytes());
+	}
+
+	/// Does an insertion sort with the recorded entry ends, each entry is
+	/// rotated into its place.
+	fn sort_indexed(&mut self, start: usize, base: usize, count: usize) {
+		for k in 1..count {
+			let entry_start = self.entry_end(start, base, Some(k - 1));
+			let entry_end = self.entry_end(start, base, Some(k));
+			let len = entry_end - entry_start;
+			// Entries are compared byte-wise, see `CollectMap` for the details.
+			let (mut low, mut high) = (0, k);
+			while low < high {
+				let mid = (low + high) / 2;
+				let other_start = self.entry_end(start, base, mid.checked_sub(1));
+				let other_end = self.entry_end(start, base, Some(mid));
+				if self.buf[entry_start..entry_end] < self.buf[other_start..other_end] {
+					high = mid;
+				} else {
+					low = mid + 1;
+				}
+			}
+			if low == k {
+				continue;
+			}
+			let at = self.entry_end(start, base, low.checked_sub(1));
+			self.buf[at..entry_end].rotate_right(len);
+			for i in (low..k).rev() {
+				let end = self.entry_end(start, base, Some(i)) + len;
+				self.set_entry_end(base, i + 1, end);
+			}
+			self.set_entry_end(base, low, at + len);
+		}
+	}
+
+	/// Does an insertion sort, where the entries are found by parsing them.
+	fn sort_parsed(
+		&mut self,
+		start: usize,
+	) -> Result<(), EncodeError<BufferFull>> {
+		let mut sorted_end = start;
+		while sorted_end < self.pos {
+			let len = entry_len(&self.buf[sorted_end..self.pos])?;
+			let entry_end = sorted_end + len;
+			let mut at = start;
+			while at < sorted_end {
+				let other_len = entry_len(&self.buf[at..sorted_end])?;
+				if self.buf[sorted_end..entry_end] < self.buf[at..at + other_len] {
+					break;
+				}
+				at += other_len;
+			}
+			self.buf[at..entry_end].rotate_right(len);
+			sorted_end = entry_end;
+		}
+		Ok(())
+	}
+}
+
+/// Returns the length of the map entry (the key and the value) at the start of
+/// `buf`.
+///
+/// The entry was written by the serializer, hence it's only split into its
+/// data items, the DAG-CBOR rules aren't checked again. This way the same
+/// values are accepted as by [`to_vec`].
+fn entry_len(buf: &[u8]) -> Result<usize, EncodeError<BufferFull>> {
+	let key_len = item_len(buf)?;
+	Ok(key_len + item_len(&buf[key_len..])?)
+}
+
+/// Returns the length of the CBOR data item at the start of `buf`.
+fn item_len(buf: &[u8]) -> Result<usize, EncodeError<BufferFull>> {
+	let (argument, mut len) = raw::header::<Infallible>(buf)
+		.map_err(|_| EncodeError::Msg("Map entry cannot be sorted.".to_string()))?;
+	match buf[0] >> 5 {
+		major::BYTES | major::STRING => len += argument as usize,
+		major::ARRAY => {
+			for _ in 0..argument {
+				len += item_len(&buf[len..])?;
+			}
+		}
+		major::MAP => {
+			for _ in 0..argument {
+				len += entry_len(&buf[len..])?;
+			}
+		}
+		major::TAG => len += item_len(&buf[len..])?,
+		_ => {}
+	}
+	Ok(len)
+}
+
+impl enc::Write for SliceWriter<'_> {
+	type Error = BufferFull;
+
+	#[inline]
+	fn push(&mut self, input: &[u8]) -> Result<(), Self::Error> {
+		let end = self.pos + input.len();
+		self.reserve(end)?;
+		self.buf[self.pos..end].copy_from_slice(input);
+		self.pos = end;
+		Ok(())
+	}
+}
+
+impl InPlaceWrite for SliceWriter<'_> {
+	#[inline]
+	fn position(&self) -> usize {
+		self.pos
+	}
+
+	fn insert(
+		&mut self,
+		position: usize,
+		bytes: &[u8],
+	) -> Result<(), Self::Error> {
+		let end = self.pos + bytes.len();
+		self.reserve(end)?;
+		self
+			.buf
+			.copy_within(position..self.pos, position + bytes.len());
+		self.buf[position..position + bytes.len()].copy_from_slice(bytes);
+		self.pos = end;
+		Ok(())
+	}
+
+	#[inline]
+	fn start_map(&self) -> Option<usize> {
+		self.index
+	}
+
+	fn end_map_entry(&mut self) {
+		if let Some(index) = self.index {
+			if self.pos + INDEX_ENTRY_LEN <= index {
+				let index = index - INDEX_ENTRY_LEN;
+				self.buf[index..index + INDEX_ENTRY_LEN]
+					.copy_from_slice(&self.pos.to_ne_bytes());
+				self.index = Some(index);
+			} else {
+				self.index = None;
+			}
+		}
+	}
+
+	fn sort_map_entries(
+		&mut self,
+		start: usize,
+		base: Option<usize>,
+	) -> Result<(), EncodeError<Self::Error>> {
+		match (base, self.index) {
+			// The index is only ever discarded, if it still exists, all entries
+			// of this map were recorded. The entries of nested maps were already
+			// removed from it.
+			(Some(base), Some(index)) => {
+				self.sort_indexed(start, base, (base - index) / INDEX_ENTRY_LEN);
+				self.index = Some(base);
+				Ok(())
+			}
+			_ => self.sort_parsed(start),
+		}
+	}
+}
+
+/// A serializer that doesn't buffer collections.
+///
+/// It produces the same output as [`Serializer`], but instead of collecting
+/// map entries and sequences of unknown length in memory, it writes them
+/// directly and fixes up the output afterwards.
+struct InPlaceSerializer<W> {
+	writer: W,
+}
+
+impl<W> InPlaceSerializer<W> {
+	fn new(writer: W) -> Self {
+		Self { writer }
+	}
+
+	fn into_inner(self) -> W {
+		self.writer
+	}
+}
+
+/// Forwards serializing a value that isn't a collection to [`Serializer`].
+macro_rules! forward_to_serializer {
+	($($method:ident($($arg:ident: $ty:ty),*);)*) => {$(
+		#[inline]
+		fn $method(self, $($arg: $ty),*) -> Result<Self::Ok, Self::Error> {
+			Serializer::new(&mut self.writer).$method($($arg),*)
+		}
+	)*};
+}
+
+impl<'a, W: InPlaceWrite> serde::Serializer for &'a mut InPlaceSerializer<W>
+where
+	W::Error: core::fmt::Debug,
+{
+	type Error = EncodeError<W::Error>;
+	type Ok = ();
+	type SerializeMap = InPlaceMap<'a, W>;
+	type SerializeSeq = InPlaceSeq<'a, W>;
+	type SerializeStruct = InPlaceMap<'a, W>;
+	type SerializeStructVariant = InPlaceMap<'a, W>;
+	type SerializeTuple = InPlaceSeq<'a, W>;
+	type SerializeTupleStruct = InPlaceSeq<'a, W>;
+	type SerializeTupleVariant = InPlaceSeq<'a, W>;
+
+	forward_to_serializer! {
+		serialize_bool(v: bool);
+		serialize_i8(v: i8);
+		serialize_i16(v: i16);
+		serialize_i32(v: i32);
+		serialize_i64(v: i64);
+		serialize_i128(v: i128);
+		serialize_u8(v: u8);
+		serialize_u16(v: u16);
+		serialize_u32(v: u32);
+		serialize_u64(v: u64);
+		serialize_u128(v: u128);
+		serialize_f32(v: f32);
+		serialize_f64(v: f64);
+		serialize_char(v: char);
+		serialize_str(v: &str);
+		serialize_bytes(v: &[u8]);
+		serialize_none();
+		serialize_unit();
+		serialize_unit_struct(name: &'static str);
+		serialize_unit_variant(
+			name: &'static str,
+			variant_index: u32,
+			variant: &'static str
+		);
+	}
+
+	#[inline]
+	fn serialize_some<T: Serialize + ?Sized>(
+		self,
+		value: &T,
+	) -> Result<Self::Ok, Self::Error> {
+		value.serialize(self)
+	}
+
+	#[inline]
+	fn serialize_newtype_struct<T: Serialize + ?Sized>(
+		self,
+		name: &'static str,
+		value: &T,
+	) -> Result<Self::Ok, Self::Error> {
+		if name == CID_SERDE_PRIVATE_IDENTIFIER
+			|| name == RAW_VALUE_SERDE_PRIVATE_IDENTIFIER
+		{
+			Serializer::new(&mut self.writer).serialize_newtype_struct(name, value)
+		} else {
+			value.serialize(self)
+		}
+	}
+
+	#[inline]
+	fn serialize_newtype_variant<T: Serialize + ?Sized>(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+		value: &T,
+	) -> Result<Self::Ok, Self::Error> {
+		enc::MapStartBounded(1).encode(&mut self.writer)?;
+		variant.encode(&mut self.writer)?;
+		value.serialize(self)
+	}
+
+	#[inline]
+	fn serialize_seq(
+		self,
+		len: Option<usize>,
+	) -> Result<Self::SerializeSeq, Self::Error> {
+		InPlaceSeq::new(self, len)
+	}
+
+	#[inline]
+	fn serialize_tuple(
+		self,
+		len: usize,
+	) -> Result<Self::SerializeTuple, Self::Error> {
+		InPlaceSeq::new(self, Some(len))
+	}
+
+	#[inline]
+	fn serialize_tuple_struct(
+		self,
+		_name: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeTupleStruct, Self::Error> {
+		InPlaceSeq::new(self, Some(len))
+	}
+
+	#[inline]
+	fn serialize_tuple_variant(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeTupleVariant, Self::Error> {
+		enc::MapStartBounded(1).encode(&mut self.writer)?;
+		variant.encode(&mut self.writer)?;
+		InPlaceSeq::new(self, Some(len))
+	}
+
+	#[inline]
+	fn serialize_map(
+		self,
+		_len: Option<usize>,
+	) -> Result<Self::SerializeMap, Self::Error> {
+		Ok(InPlaceMap::new(self, true))
+	}
+
+	#[inline]
+	fn serialize_struct(
+		self,
+		_name: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeStruct, Self::Error> {
+		enc::MapStartBounded(len).encode(&mut self.writer)?;
+		Ok(InPlaceMap::new(self, false))
+	}
+
+	#[inline]
+	fn serialize_struct_variant(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		variant: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeStructVariant, Self::Error> {
+		enc::MapStartBounded(1).encode(&mut self.writer)?;
+		variant.encode(&mut self.writer)?;
+		enc::MapStartBounded(len).encode(&mut self.writer)?;
+		Ok(InPlaceMap::new(self, false))
+	}
+
+	#[inline]
+	fn is_human_readable(&self) -> bool {
+		false
+	}
+}
+
+/// Helper for processing sequences in place.
+struct InPlaceSeq<'a, W> {
+	ser: &'a mut InPlaceSerializer<W>,
+	/// Where the elements start.
+	start: usize,
+	/// The number of elements, in case the header still needs to be written.
+	count: Option<usize>,
+}
+
+impl<'a, W: InPlaceWrite> InPlaceSeq<'a, W> {
+	/// If the length of the sequence is given, the header is written right
+	/// away. Else it's inserted in front of the elements once they are all
+	/// written.
+	fn new(
+		ser: &'a mut InPlaceSerializer<W>,
+		len: Option<usize>,
+	) -> Result<Self, EncodeError<W::Error>> {
+		let count = if let Some(len) = len {
+			enc::ArrayStartBounded(len).encode(&mut ser.writer)?;
+			None
+		} else {
+			Some(0)
+		};
+		Ok(Self {
+			start: ser.writer.position(),
+			ser,
+			count,
+		})
+	}
+
+	fn serialize_element<T: Serialize + ?Sized>(
+		&mut self,
+		value: &T,
+	) -> Result<(), EncodeError<W::Error>>
+	where
+		W::Error: core::fmt::Debug,
+	{
+		if let Some(count) = self.count.as_mut() {
+			*count += 1;
+		}
+		value.serialize(&mut *self.ser)
+	}
+
+	fn end(self) -> Result<(), EncodeError<W::Error>> {
+		if let Some(count) = self.count {
+			let (header, len) = encode_header(major::ARRAY, count as u64);
+			self.ser.writer.insert(self.start, &header[..len])?;
+		}
+		Ok(())
+	}
+}
+
+impl<W: InPlaceWrite> serde::ser::SerializeSeq for InPlaceSeq<'_, W>
+where
+	W::Error: core::fmt::Debug,
+{
+	type Error = EncodeError<W::Error>;
+	type Ok = ();
+
+	#[inline]
+	fn serialize_element<T: Serialize + ?Sized>(
+		&mut self,
+		value: &T,
+	) -> Result<(), Self::Error> {
+		self.serialize_element(value)
+	}
+
+	#[inline]
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.end()
+	}
+}
+
+impl<W: InPlaceWrite> serde::ser::SerializeTuple for InPlaceSeq<'_, W>
+where
+	W::Error: core::fmt::Debug,
+{
+	type Error = EncodeError<W::Error>;
+	type Ok = ();
+
+	#[inline]
+	fn serialize_element<T: Serialize + ?Sized>(
+		&mut self,
+		value: &T,
+	) -> Result<(), Self::Error> {
+		self.serialize_element(value)
+	}
+
+	#[inline]
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.end()
+	}
+}
+
+impl<W: InPlaceWrite> serde::ser::SerializeTupleStruct for InPlaceSeq<'_, W>
+where
+	W::Error: core::fmt::Debug,
+{
+	type Error = EncodeError<W::Error>;
+	type Ok = ();
+
+	#[inline]
+	fn serialize_field<T: Serialize + ?Sized>(
+		&mut self,
+		value: &T,
+	) -> Result<(), Self::Error> {
+		self.serialize_element(value)
+	}
+
+	#[inline]
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.end()
+	}
+}
+
+impl<W: InPlaceWrite> serde::ser::SerializeTupleVariant for InPlaceSeq<'_, W>
+where
+	W::Error: core::fmt::Debug,
+{
+	type Error = EncodeError<W::Error>;
+	type Ok = ();
+
+	#[inline]
+	fn serialize_field<T: Serialize + ?Sized>(
+		&mut self,
+		value: &T,
+	) -> Result<(), Self::Error> {
+		self.serialize_element(value)
+	}
+
+	#[inline]
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.end()
+	}
+}
+
+/// Helper for processing maps in place.
+///
+/// The entries are written as they come and sorted into the canonical order
+/// (see [`CollectMap`]) once all of them were written.
+struct InPlaceMap<'a, W> {
+	ser: &'a mut InPlaceSerializer<W>,
+	/// Where the entries start.
+	start: usize,
+	/// The number of entries.
+	count: usize,
+	/// The token for the recorded ends of the entries.
+	index: Option<usize>,
+	/// Whether the header needs to be inserted once all entries are written.
+	insert_header: bool,
+}
+
+impl<'a, W: InPlaceWrite> InPlaceMap<'a, W>
+where
+	W::Error: core::fmt::Debug,
+{
+	fn new(ser: &'a mut InPlaceSerializer<W>, insert_header: bool) -> Self {
+		Self {
+			start: ser.writer.position(),
+			index: ser.writer.start_map(),
+			ser,
+			count: 0,
+			insert_header,
+		}
+	}
+
+	fn serialize_field<T: Serialize + ?Sized>(
+		&mut self,
+		key: &'static str,
+		value: &T,
+	) -> Result<(), EncodeError<W::Error>> {
+		key.serialize(&mut *self.ser)?;
+		self.serialize_value(value)
+	}
+
+	fn serialize_value<T: Serialize + ?Sized>(
+		&mut self,
+		value: &T,
+	) -> Result<(), EncodeError<W::Error>> {
+		self.count += 1;
+		value.serialize(&mut *self.ser)?;
+		self.ser.writer.end_map_entry();
+		Ok(())
+	}
+
+	fn end(self) -> Result<(), EncodeError<W::Error>> {
+		self.ser.writer.sort_map_entries(self.start, self.index)?;
+		if self.insert_header {
+			let (header, len) = encode_header(major::MAP, self.count as u64);
+			self.ser.writer.insert(self.start, &header[..len])?;
+		}
+		Ok(())
+	}
+}
+
+impl<W: InPlaceWrite> serde::ser::SerializeMap for InPlaceMap<'_, W>
+where
+	W::Error: core::fmt::Debug,
+{
+	type Error = EncodeError<W::Error>;
+	type Ok = ();
+
+	#[inline]
+	fn serialize_key<T: Serialize + ?Sized>(
+		&mut self,
+		key: &T,
+	) -> Result<(), Self::Error> {
+		key.serialize(&mut *self.ser)
+	}
+
+	#[inline]
+	fn serialize_value<T: Serialize + ?Sized>(
+		&mut self,
+		value: &T,
+	) -> Result<(), Self::Error> {
+		self.serialize_value(value)
+	}
+
+	#[inline]
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.end()
+	}
+}
+
+impl<W: InPlaceWrite> serde::ser::SerializeStruct for InPlaceMap<'_, W>
+where
+	W::Error: core::fmt::Debug,
+{
+	type Error = EncodeError<W::Error>;
+	type Ok = ();
+
+	#[inline]
+	fn serialize_field<T: Serialize + ?Sized>(
+		&mut self,
+		key: &'static str,
+		value: &T,
+	) -> Result<(), Self::Error> {
+		self.serialize_field(key, value)
+	}
+
+	#[inline]
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.end()
+	}
+}
+
+impl<W: InPlaceWrite> serde::ser::SerializeStructVariant for InPlaceMap<'_, W>
+where
+	W::Error: core::fmt::Debug,
+{
+	type Error = EncodeError<W::Error>;
+	type Ok = ();
+
+	#[inline]
+	fn serialize_field<T: Serialize + ?Sized>(
+		&mut self,
+		key: &'static str,
+		value: &T,
+	) -> Result<(), Self::Error> {
+		self.serialize_field(key, value)
+	}
+
+	#[inline]
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		self.end()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::*,
+		crate::{cid::Cid, dag::to_raw_value, ipld::Ipld},
+		alloc::{collections::BTreeMap, string::String, vec},
+		serde::ser::{SerializeMap, SerializeSeq},
+		serde_derive::Serialize,
+	};
+
+	#[derive(Serialize)]
+	struct Block {
+		height: u64,
+		parent: Option<Cid>,
+		#[serde(with = "serde_bytes")]
+		data: Vec<u8>,
+		kind: Kind,
+	}
+
+	#[derive(Serialize)]
+	enum Kind {
+		Genesis,
+		Child { depth: u32 },
+	}
+
+	/// Serializes its content as collections of unknown length.
+	struct Unsized(Vec<(String, i64)>);
+
+	impl Serialize for Unsized {
+		fn serialize<S: serde::Serializer>(
+			&self,
+			serializer: S,
+		) -> Result<S::Ok, S::Error> {
+			let mut seq = serializer.serialize_seq(None)?;
+			for (key, value) in &self.0 {
+				seq.serialize_element(&UnsizedMap(key, *value))?;
+			}
+			seq.end()
+		}
+	}
+
+	struct UnsizedMap<'a>(&'a str, i64);
+
+	impl Serialize for UnsizedMap<'_> {
+		fn serialize<S: serde::Serializer>(
+			&self,
+			serializer: S,
+		) -> Result<S::Ok, S::Error> {
+			let mut map = serializer.serialize_map(None)?;
+			map.serialize_entry(self.0, &self.1)?;
+			map.serialize_entry("x", &())?;
+			map.end()
+		}
+	}
+
+	fn assert_exact<T: Serialize + ?Sized>(value: &T) {
+		let encoded = to_vec(value).unwrap();
+		assert_eq!(encoded_len(value).unwrap(), encoded.len());
+
+		// With room for the map index and without.
+		for spare in [0, 10, 10 * encoded.len()] {
+			let mut buf = vec![0; encoded.len() + spare];
+			assert_eq!(to_slice(&mut buf, value).unwrap(), encoded.len());
+			assert_eq!(&buf[..encoded.len()], encoded);
+		}
+	}
+
+	#[test]
+	fn test_encoded_len_struct() {
+		let cid = Cid::try_from(
+			"bafyreibjo4xmgaevkgud7mbifn3dzp4v4lyaui4yvqp3f2bqwtxcjrdqg4",
+		)
+		.unwrap();
+		assert_exact(&Block {
+			height: 1234,
+			parent: Some(cid),
+			data: vec![7; 300],
+			kind: Kind::Child { depth: 70000 },
+		});
+		assert_exact(&Block {
+			height: u64::MAX,
+			parent: None,
+			data: Vec::new(),
+			kind: Kind::Genesis,
+		});
+	}
+
+	#[test]
+	fn test_encoded_len_ipld() {
+		let cid = Cid::try_from(
+			"bafyreibjo4xmgaevkgud7mbifn3dzp4v4lyaui4yvqp3f2bqwtxcjrdqg4",
+		)
+		.unwrap();
+		let list = (0..1000).map(Ipld::Integer).collect();
+		let ipld = Ipld::Map(BTreeMap::from([
+			("link".into(), Ipld::Link(cid)),
+			("list".into(), Ipld::List(list)),
+			("float".into(), Ipld::Float(1.5)),
+			("string".into(), Ipld::String("a".repeat(70000))),
+			("null".into(), Ipld::Null),
+		]));
+		assert_exact(&ipld);
+		assert_exact(&Ipld::Map(BTreeMap::new()));
+	}
+
+	#[test]
+	fn test_encoded_len_unknown_length() {
+		let entries = (0..30).map(|i| ("a".repeat(i), -(i as i64))).collect();
+		assert_exact(&Unsized(entries));
+		assert_exact(&Unsized(Vec::new()));
+	}
+
+	#[test]
+	fn test_encoded_len_nested_maps() {
+		let inner = |i: i128| {
+			Ipld::Map(BTreeMap::from([
+				("z".repeat(i as usize % 7), Ipld::Integer(i)),
+				("b".into(), Ipld::List(vec![Ipld::Map(BTreeMap::new())])),
+			]))
+		};
+		let ipld = Ipld::Map(
+			(0..100)
+				.map(|i| (alloc::format!("{}", i * 37 % 101), inner(i)))
+				.collect(),
+		);
+		assert_exact(&ipld);
+	}
+
+	#[test]
+	fn test_encoded_len_agrees_with_to_vec() {
+		// DAG-CBOR only allows string keys, but none of the encoders check it.
+		assert_exact(&BTreeMap::from([(300, "a"), (1, "b"), (-2, "c")]));
+
+		// The nesting isn't limited to the recursion limit of the decoder.
+		let mut deep = Ipld::Map(BTreeMap::from([("b".into(), Ipld::Null)]));
+		for _ in 0..300 {
+			deep = Ipld::Map(BTreeMap::from([
+				("b".into(), deep),
+				("a".into(), Ipld::Null),
+			]));
+		}
+		assert_exact(&deep);
+	}
+
+	#[test]
+	fn test_encoded_len_raw_value() {
+		let raw = to_raw_value(&(1, "two", [3.0])).unwrap();
+		assert_exact(&vec![raw.clone(), raw]);
+	}
+
+	#[test]
+	fn test_to_slice_buffer_full() {
+		let ipld = Ipld::Map(BTreeMap::from([
+			("bbb".into(), Ipld::List(vec![Ipld::Integer(1)])),
+			("c".into(), Ipld::String("a".repeat(30))),
+		]));
+		let unsized_ = Unsized(vec![("bb".into(), 1), ("a".into(), 2)]);
+		let encoded_ipld = to_vec(&ipld).unwrap();
+		let encoded_unsized = to_vec(&unsized_).unwrap();
+
+		let mut buf = [0; 64];
+		for len in 0..encoded_ipld.len() {
+			assert!(matches!(
+				to_slice(&mut buf[..len], &ipld),
+				Err(EncodeError::Write(BufferFull))
+			));
+		}
+		for len in 0..encoded_unsized.len() {
+			assert!(matches!(
+				to_slice(&mut buf[..len], &unsized_),
+				Err(EncodeError::Write(BufferFull))
+			));
+		}
+		let len = to_slice(&mut buf, &ipld).unwrap();
+		assert_eq!(&buf[..len], encoded_ipld);
+	}
+
+	#[test]
+	fn test_encoded_len_error() {
+		assert!(encoded_len(&u128::MAX).is_err());
+	}
+}
@@ -14,7 +14,7 @@ pub mod __private_do_not_use {
 }
 
 use {
-	crate::cid::Cid,
+	crate::{cid::Cid, multicodec::Multicodec},
 	::alloc::{
 		borrow::ToOwned,
 		boxed::Box,
@@ -26,6 +26,9 @@ use {
 	core::fmt,
 };
 
+/// The codec of DAG-CBOR encoded blocks.
+const DAG_CBOR: u64 = Multicodec::DagCbor.code();
+
 /// Error when accessing IPLD List or Map elements.
 #[derive(Clone, Debug)]
 #[non_exhaustive]
@@ -301,6 +304,28 @@ impl Ipld {
 			}
 		}
 	}
+
+	/// Returns the references to other blocks that need to be fetched from a
+	/// block store.
+	///
+	/// Inline CIDs are skipped, as their data is part of the CID. If the inlined
+	/// data is DAG-CBOR, its references are returned instead.
+	pub fn stored_references<E: Extend<Cid>>(&self, set: &mut E) {
+		for ipld in self.iter() {
+			if let Ipld::Link(cid) = ipld {
+				match cid.inline_data() {
+					None => set.extend(core::iter::once(cid.to_owned())),
+					Some(data) if cid.codec() == DAG_CBOR => {
+						// Data that isn't valid DAG-CBOR can't contain any links.
+						if let Ok(inlined) = crate::dag::from_slice::<Ipld>(data) {
+							inlined.stored_references(set);
+						}
+					}
+					Some(_) => {}
+				}
+			}
+		}
+	}
 }
 
 /// Ipld iterator.
@@ -337,7 +362,10 @@ impl<'a> Iterator for IpldIter<'a> {
 
 #[cfg(test)]
 mod tests {
-	use super::*;
+	use {
+		super::*,
+		crate::{cid::InlinePolicy, multihash::Multihash},
+	};
 
 	#[test]
 	fn test_ipld_bool_from() {
@@ -434,4 +462,30 @@ mod tests {
 		let invalid_ipld = Ipld::Float(f64::NAN);
 		assert_eq!(invalid_ipld, invalid_ipld);
 	}
+
+	#[test]
+	fn test_stored_references() {
+		let stored = Cid::new_v1(0x55, Multihash::wrap(0x12, &[1; 32]).unwrap());
+		let nested = Cid::new_v1(0x55, Multihash::wrap(0x12, &[2; 32]).unwrap());
+		let inline_raw = Cid::new_inline(0x55, b"raw").unwrap();
+		let inline_cbor = InlinePolicy::new(64)
+			.new_cid(
+				DAG_CBOR,
+				&crate::dag::to_vec(&Ipld::List(vec![Ipld::Link(nested)])).unwrap(),
+			)
+			.unwrap();
+		let ipld = Ipld::List(vec![
+			Ipld::Link(stored),
+			Ipld::Link(inline_raw),
+			Ipld::Link(inline_cbor),
+		]);
+
+		let mut all = Vec::new();
+		ipld.references(&mut all);
+		assert_eq!(all, [stored, inline_raw, inline_cbor]);
+
+		let mut references = Vec::new();
+		ipld.stored_references(&mut references);
+		assert_eq!(references, [stored, nested]);
+	}
 }
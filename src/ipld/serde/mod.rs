@@ -53,7 +53,7 @@ mod tests {
 		core::fmt,
 		serde::{de::DeserializeOwned, Serialize},
 		serde_derive::Deserialize,
-		serde_test::{assert_tokens, Token},
+		serde_test::{assert_tokens, Configure, Token},
 	};
 
 	/// Utility for testing (de)serialization of [`Ipld`].
@@ -97,7 +97,7 @@ mod tests {
 	fn test_tokens() {
 		let person = Person::default();
 
-		assert_tokens(&person, &[
+		assert_tokens(&person.compact(), &[
 			Token::Struct {
 				name: "Person",
 				len: 5,
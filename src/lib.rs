@@ -7,9 +7,9 @@ pub mod cid;
 pub mod dag;
 pub mod ipld;
 pub mod multibase;
+pub mod multicodec;
 pub mod multihash;
-
-mod varint;
+pub mod varint;
 
 pub use {
 	car::{CarHeader, CarReader, CarWriter},
@@ -7,7 +7,7 @@ use {
 };
 
 macro_rules! build_base_enum {
-    ( $(#[$attr:meta] $code:expr => $base:ident,)* ) => {
+    ( $(#[$attr:meta] $code:expr => $base:ident($name:literal),)* ) => {
         /// List of types currently supported in the multibase spec.
         ///
         /// Not all base types are supported by this library.
@@ -32,6 +32,13 @@ macro_rules! build_base_enum {
                 }
             }
 
+            /// Get the name of the base algorithm, as used in the multibase spec.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $( Self::$base => $name, )*
+                }
+            }
+
             /// Encode the given byte slice to base string.
             pub fn encode<I: AsRef<[u8]>>(&self, input: I) -> String {
                 match self {
@@ -45,57 +52,140 @@ macro_rules! build_base_enum {
                     $( Self::$base => $base::decode(input), )*
                 }
             }
+
+            /// Encode the given byte slice into the output, without allocating.
+            ///
+            /// Returns the number of bytes written, or `Error::BufferTooSmall` if
+            /// the output is shorter than needed. An output of
+            /// [`Base::max_encoded_len`] bytes is always large enough.
+            pub fn encode_to_slice(&self, input: &[u8], output: &mut [u8]) -> Result<usize> {
+                match self {
+                    $( Self::$base => $base::encode_to_slice(input, output), )*
+                }
+            }
+
+            /// Decode the base string, accepting only its canonical form.
+            ///
+            /// Unlike [`Base::decode`], this rejects characters in the wrong case,
+            /// bad padding and non-zero trailing bits, so that every byte string has
+            /// exactly one valid encoding. The errors carry the byte position in
+            /// the input.
+            pub fn decode_strict<I: AsRef<str>>(&self, input: I) -> Result<Vec<u8>> {
+                match self {
+                    $( Self::$base => $base::decode_strict(input.as_ref()), )*
+                }
+            }
+
+            /// Decode the base string into the output, without allocating.
+            ///
+            /// Returns the number of bytes written, or `Error::BufferTooSmall` if
+            /// the output is shorter than needed. An output of
+            /// [`Base::max_decoded_len`] bytes is always large enough.
+            pub fn decode_to_slice<I: AsRef<str>>(&self, input: I, output: &mut [u8]) -> Result<usize> {
+                match self {
+                    $( Self::$base => $base::decode_to_slice(input.as_ref(), output), )*
+                }
+            }
+
+            /// Get the maximum length of the encoded string for an input of `len` bytes.
+            ///
+            /// The length is exact for the bases that encode blocks of bits, i.e.
+            /// all but base10, base36, base58 and base256emoji. See
+            /// [`Base::encoded_len`] for the exact length of a given input.
+            pub fn max_encoded_len(&self, len: usize) -> usize {
+                match self {
+                    $( Self::$base => $base::max_encoded_len(len), )*
+                }
+            }
+
+            /// Get the maximum length of the decoded bytes for a string of `len` bytes.
+            ///
+            /// The length is exact for the bases that encode blocks of bits without
+            /// padding, i.e. all but base10, base36, base58, base256emoji and the padded
+            /// bases. See [`Base::decoded_len`] for the exact length of a given input.
+            pub fn max_decoded_len(&self, len: usize) -> usize {
+                match self {
+                    $( Self::$base => $base::max_decoded_len(len), )*
+                }
+            }
+
+            /// Get the exact length of the encoded string for the input.
+            ///
+            /// For base10, base36 and base58 the length depends on the value of the
+            /// input, it's converted in a buffer of 256 bytes on the stack. Past
+            /// that this is [`Base::max_encoded_len`].
+            pub fn encoded_len(&self, input: &[u8]) -> usize {
+                match self {
+                    $( Self::$base => $base::encoded_len(input), )*
+                }
+            }
+
+            /// Get the exact length of the decoded bytes for the base string.
+            ///
+            /// Returns an error if no encoded string has the length of the input.
+            /// The characters aren't checked, except for base10, base36 and base58,
+            /// where the input is converted in a buffer of 256 bytes on the stack.
+            /// Past that this is [`Base::max_decoded_len`].
+            pub fn decoded_len<I: AsRef<str>>(&self, input: I) -> Result<usize> {
+                match self {
+                    $( Self::$base => $base::decoded_len(input.as_ref()), )*
+                }
+            }
         }
     }
 }
 
 build_base_enum! {
 		/// 8-bit binary (encoder and decoder keeps data unmodified).
-		'\x00' => Identity,
+		'\x00' => Identity("identity"),
 		/// Base2 (alphabet: 01).
-		'0' => Base2,
+		'0' => Base2("base2"),
 		/// Base8 (alphabet: 01234567).
-		'7' => Base8,
+		'7' => Base8("base8"),
 		/// Base10 (alphabet: 0123456789).
-		'9' => Base10,
+		'9' => Base10("base10"),
 		/// Base16 lower hexadecimal (alphabet: 0123456789abcdef).
-		'f' => Base16Lower,
+		'f' => Base16Lower("base16"),
 		/// Base16 upper hexadecimal (alphabet: 0123456789ABCDEF).
-		'F' => Base16Upper,
+		'F' => Base16Upper("base16upper"),
 		 /// Base32, rfc4648 no padding (alphabet: abcdefghijklmnopqrstuvwxyz234567).
-		'b' => Base32Lower,
+		'b' => Base32Lower("base32"),
 		/// Base32, rfc4648 no padding (alphabet: ABCDEFGHIJKLMNOPQRSTUVWXYZ234567).
-		'B' => Base32Upper,
+		'B' => Base32Upper("base32upper"),
 		/// Base32, rfc4648 with padding (alphabet: abcdefghijklmnopqrstuvwxyz234567).
-		'c' => Base32PadLower,
+		'c' => Base32PadLower("base32pad"),
 		/// Base32, rfc4648 with padding (alphabet: ABCDEFGHIJKLMNOPQRSTUVWXYZ234567).
-		'C' => Base32PadUpper,
+		'C' => Base32PadUpper("base32padupper"),
 		/// Base32hex, rfc4648 no padding (alphabet: 0123456789abcdefghijklmnopqrstuv).
-		'v' => Base32HexLower,
+		'v' => Base32HexLower("base32hex"),
 		/// Base32hex, rfc4648 no padding (alphabet: 0123456789ABCDEFGHIJKLMNOPQRSTUV).
-		'V' => Base32HexUpper,
+		'V' => Base32HexUpper("base32hexupper"),
 		/// Base32hex, rfc4648 with padding (alphabet: 0123456789abcdefghijklmnopqrstuv).
-		't' => Base32HexPadLower,
+		't' => Base32HexPadLower("base32hexpad"),
 		/// Base32hex, rfc4648 with padding (alphabet: 0123456789ABCDEFGHIJKLMNOPQRSTUV).
-		'T' => Base32HexPadUpper,
+		'T' => Base32HexPadUpper("base32hexpadupper"),
 		/// z-base-32 (used by Tahoe-LAFS) (alphabet: ybndrfg8ejkmcpqxot1uwisza345h769).
-		'h' => Base32Z,
+		'h' => Base32Z("base32z"),
 		/// Base36, [0-9a-z] no padding (alphabet: 0123456789abcdefghijklmnopqrstuvwxyz).
-		'k' => Base36Lower,
+		'k' => Base36Lower("base36"),
 		/// Base36, [0-9A-Z] no padding (alphabet: 0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ).
-		'K' => Base36Upper,
+		'K' => Base36Upper("base36upper"),
+		/// Base45, rfc9285 (alphabet: 0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:).
+		'R' => Base45("base45"),
 		/// Base58 flicker (alphabet: 123456789abcdefghijkmnopqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ).
-		'Z' => Base58Flickr,
+		'Z' => Base58Flickr("base58flickr"),
 		/// Base58 bitcoin (alphabet: 123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz).
-		'z' => Base58Btc,
+		'z' => Base58Btc("base58btc"),
 		/// Base64, rfc4648 no padding (alphabet: ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/).
-		'm' => Base64,
+		'm' => Base64("base64"),
 		/// Base64, rfc4648 with padding (alphabet: ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/).
-		'M' => Base64Pad,
+		'M' => Base64Pad("base64pad"),
 		/// Base64 url, rfc4648 no padding (alphabet: ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_).
-		'u' => Base64Url,
+		'u' => Base64Url("base64url"),
 		/// Base64 url, rfc4648 with padding (alphabet: ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_).
-		'U' => Base64UrlPad,
+		'U' => Base64UrlPad("base64urlpad"),
+		/// Proquint, pronounceable words of 16 bits (consonants: bdfghjklmnprstvz, vowels: aiou).
+		'p' => Proquint("proquint"),
 /// Base256Emoji (alphabet: 🚀🪐☄🛰🌌🌑🌒🌓🌔🌕🌖🌗🌘🌍🌏🌎🐉☀💻🖥💾💿😂❤😍🤣😊🙏💕😭😘👍😅👏😁🔥🥰💔💖💙😢🤔😆🙄💪😉☺👌🤗💜😔😎😇🌹🤦🎉💞✌✨🤷😱😌🌸🙌😋💗💚😏💛🙂💓🤩😄😀🖤😃💯🙈👇🎶😒🤭❣😜💋👀😪😑💥🙋😞😩😡🤪👊🥳😥🤤👉💃😳✋😚😝😴🌟😬🙃🍀🌷😻😓⭐✅🥺🌈😈🤘💦✔😣🏃💐☹🎊💘😠☝😕🌺🎂🌻😐🖕💝🙊😹🗣💫💀👑🎵🤞😛🔴😤🌼😫⚽🤙☕🏆🤫👈😮🙆🍻🍃🐶💁😲🌿🧡🎁⚡🌞🎈❌✊👋😰🤨😶🤝🚶💰🍓💢🤟🙁🚨💨🤬✈🎀🍺🤓😙💟🌱😖👶🥴▶➡❓💎💸⬇😨🌚🦋😷🕺⚠🙅😟😵👎🤲🤠🤧📌🔵💅🧐🐾🍒😗🤑🌊🤯🐷☎💧😯💆👆🎤🙇🍑❄🌴💣🐸💌📍🥀🤢👅💡💩👐📸👻🤐🤮🎼🥵🚩🍎🍊👼💍📣🥂)
-		'🚀' => Base256Emoji,
+		'🚀' => Base256Emoji("base256emoji"),
 }
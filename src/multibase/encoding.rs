@@ -91,6 +91,9 @@ pub const BASE36_LOWER: &str = "0123456789abcdefghijklmnopqrstuvwxyz";
 /// Base36, [0-9A-Z] no padding (alphabet: 0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ).
 pub const BASE36_UPPER: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
 
+/// Base45, rfc9285 (alphabet: 0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:).
+pub const BASE45: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
 // Base58 Flickr's alphabet for creating short urls from photo ids.
 pub const BASE58_FLICKR: &str = "123456789abcdefghijkmnopqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ";
 
@@ -108,3 +111,9 @@ pub const BASE64URL_NOPAD: Encoding = data_encoding::BASE64URL_NOPAD;
 
 // Base64 url, rfc4648 with padding (alphabet: ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_).
 pub const BASE64URL_PAD: Encoding = data_encoding::BASE64URL;
+
+/// Proquint consonants, each encoding 4 bits.
+pub const PROQUINT_CONSONANTS: &str = "bdfghjklmnprstvz";
+
+/// Proquint vowels, each encoding 2 bits.
+pub const PROQUINT_VOWELS: &str = "aiou";
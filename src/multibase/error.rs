@@ -1,4 +1,4 @@
-use core::fmt;
+use {super::Base, core::fmt};
 
 /// Type alias to use this library's [`Error`] type in a `Result`.
 pub type Result<T> = core::result::Result<T, Error>;
@@ -10,6 +10,43 @@ pub enum Error {
 	UnknownBase(char),
 	/// Invalid string.
 	InvalidBaseString,
+	/// A character that isn't part of the alphabet, at the given byte position.
+	InvalidCharacter {
+		/// The offending character.
+		character: char,
+		/// The byte position of the character.
+		position: usize,
+	},
+	/// The length of the string is invalid for the base.
+	InvalidLength,
+	/// Invalid padding or non-zero trailing bits, at the given byte position.
+	NonCanonical {
+		/// The byte position of the first non-canonical character.
+		position: usize,
+	},
+	/// The output buffer is too small.
+	BufferTooSmall,
+	/// The base can't be encoded or decoded in chunks.
+	NotStreamable(Base),
+}
+
+impl Error {
+	/// Moves the position of the error by the offset of the decoded part.
+	pub(crate) fn offset(self, offset: usize) -> Self {
+		match self {
+			Error::InvalidCharacter {
+				character,
+				position,
+			} => Error::InvalidCharacter {
+				character,
+				position: position + offset,
+			},
+			Error::NonCanonical { position } => Error::NonCanonical {
+				position: position + offset,
+			},
+			err => err,
+		}
+	}
 }
 
 impl fmt::Display for Error {
@@ -17,6 +54,24 @@ impl fmt::Display for Error {
 		match self {
 			Error::UnknownBase(code) => write!(f, "Unknown base code: {}", code),
 			Error::InvalidBaseString => write!(f, "Invalid base string"),
+			Error::InvalidCharacter {
+				character,
+				position,
+			} => {
+				write!(
+					f,
+					"Invalid character {:?} at position {}",
+					character, position
+				)
+			}
+			Error::InvalidLength => write!(f, "Invalid length of base string"),
+			Error::NonCanonical { position } => {
+				write!(f, "Non-canonical base string at position {}", position)
+			}
+			Error::BufferTooSmall => write!(f, "Output buffer too small"),
+			Error::NotStreamable(base) => {
+				write!(f, "Base {} can't be streamed", base.name())
+			}
 		}
 	}
 }
@@ -1,7 +1,11 @@
 use {
-	super::{encoding, error::Result},
+	super::{
+		encoding,
+		error::{Error, Result},
+	},
 	alloc::{string::String, vec::Vec},
 	base256emoji::{Base, Emoji},
+	data_encoding::{DecodeKind, Encoding},
 };
 
 macro_rules! derive_base_encoding {
@@ -16,9 +20,44 @@ macro_rules! derive_base_encoding {
                     $encoding.encode(input.as_ref())
                 }
 
+                fn encode_to_slice(input: &[u8], output: &mut [u8]) -> Result<usize> {
+                    let len = $encoding.encode_len(input.len());
+                    let output = output.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+                    $encoding.encode_mut(input, output);
+                    Ok(len)
+                }
+
                 fn decode<I: AsRef<str>>(input: I) -> Result<Vec<u8>> {
                     Ok($encoding.decode(input.as_ref().as_bytes())?)
                 }
+
+                fn decode_strict(input: &str) -> Result<Vec<u8>> {
+                    let decoded = $encoding
+                        .decode(input.as_bytes())
+                        .map_err(|err| strict_error(input, err))?;
+                    check_canonical(input, &$encoding.encode(&decoded))?;
+                    Ok(decoded)
+                }
+
+                fn decode_to_slice(input: &str, output: &mut [u8]) -> Result<usize> {
+                    let len = Self::decoded_len(input)?;
+                    let output = output.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+                    decode_blocks(&$encoding, input, output)
+                }
+
+                fn max_encoded_len(len: usize) -> usize {
+                    $encoding.encode_len(len)
+                }
+
+                fn max_decoded_len(len: usize) -> usize {
+                    len * $encoding.bit_width() / 8
+                }
+
+                fn decoded_len(input: &str) -> Result<usize> {
+                    $encoding.decode_len(input.len())?;
+                    let len = input.trim_end_matches('=').len();
+                    Ok(len * $encoding.bit_width() / 8)
+                }
             }
         )*
     };
@@ -36,9 +75,38 @@ macro_rules! derive_base_x {
                     base_x::encode($encoding, input.as_ref())
                 }
 
+                fn encode_to_slice(input: &[u8], output: &mut [u8]) -> Result<usize> {
+                    encode_base_x($encoding, input, output)
+                }
+
                 fn decode<I: AsRef<str>>(input: I) -> Result<Vec<u8>> {
                     Ok(base_x::decode($encoding, input.as_ref())?)
                 }
+
+                fn decode_strict(input: &str) -> Result<Vec<u8>> {
+                    check_alphabet($encoding, input)?;
+                    Ok(base_x::decode($encoding, input)?)
+                }
+
+                fn decode_to_slice(input: &str, output: &mut [u8]) -> Result<usize> {
+                    decode_base_x($encoding, input, output, false)
+                }
+
+                fn max_encoded_len(len: usize) -> usize {
+                    max_base_x_encoded_len($encoding, len)
+                }
+
+                fn max_decoded_len(len: usize) -> usize {
+                    len
+                }
+
+                fn encoded_len(input: &[u8]) -> usize {
+                    base_x_encoded_len($encoding, input)
+                }
+
+                fn decoded_len(input: &str) -> Result<usize> {
+                    base_x_decoded_len($encoding, input, false)
+                }
             }
         )*
     };
@@ -48,8 +116,257 @@ pub(crate) trait BaseCodec {
 	/// Encode with the given byte slice.
 	fn encode<I: AsRef<[u8]>>(input: I) -> String;
 
+	/// Encode with the given byte slice into the output, without allocating.
+	///
+	/// Returns the number of bytes written.
+	fn encode_to_slice(input: &[u8], output: &mut [u8]) -> Result<usize>;
+
 	/// Decode with the given string.
 	fn decode<I: AsRef<str>>(input: I) -> Result<Vec<u8>>;
+
+	/// Decode with the given string, rejecting any input that isn't exactly
+	/// what encoding would produce.
+	fn decode_strict(input: &str) -> Result<Vec<u8>>;
+
+	/// Decode with the given string into the output, without allocating.
+	///
+	/// Returns the number of bytes written.
+	fn decode_to_slice(input: &str, output: &mut [u8]) -> Result<usize>;
+
+	/// Returns the maximum length of the encoded input of the given length.
+	fn max_encoded_len(len: usize) -> usize;
+
+	/// Returns the maximum length of the decoded input of the given length.
+	fn max_decoded_len(len: usize) -> usize;
+
+	/// Returns the exact length of the encoded input.
+	///
+	/// The default is for the bases where it only depends on the length of the
+	/// input.
+	fn encoded_len(input: &[u8]) -> usize {
+		Self::max_encoded_len(input.len())
+	}
+
+	/// Returns the exact length of the decoded input, or an error if its length
+	/// is invalid.
+	fn decoded_len(input: &str) -> Result<usize>;
+}
+
+/// Encodes into an ASCII alphabet of any size, the same way `base_x` does,
+/// but using the output as scratch space instead of allocating.
+fn encode_base_x(
+	alphabet: &str,
+	input: &[u8],
+	output: &mut [u8],
+) -> Result<usize> {
+	let alphabet = alphabet.as_bytes();
+	let base = alphabet.len() as u32;
+	// The digits are accumulated in little-endian order.
+	let mut len = 0;
+	let zeros = input.iter().take_while(|byte| **byte == 0).count();
+	for byte in &input[zeros..] {
+		let mut carry = u32::from(*byte);
+		for digit in &mut output[..len] {
+			carry += u32::from(*digit) << 8;
+			*digit = (carry % base) as u8;
+			carry /= base;
+		}
+		while carry > 0 {
+			*output.get_mut(len).ok_or(Error::BufferTooSmall)? = (carry % base) as u8;
+			len += 1;
+			carry /= base;
+		}
+	}
+	// Every leading zero byte is encoded as the first character of the alphabet.
+	for _ in 0..zeros {
+		*output.get_mut(len).ok_or(Error::BufferTooSmall)? = 0;
+		len += 1;
+	}
+	let output = &mut output[..len];
+	output.reverse();
+	for digit in output {
+		*digit = alphabet[usize::from(*digit)];
+	}
+	Ok(len)
+}
+
+/// Decodes a bit-block encoding into an output of exactly the decoded length.
+///
+/// `decode_mut` wants an output of the maximum length, which is larger when the
+/// input is padded. Padding only occurs in the last block, so that one goes
+/// through a buffer on the stack.
+fn decode_blocks(
+	encoding: &Encoding,
+	input: &str,
+	output: &mut [u8],
+) -> Result<usize> {
+	let decode = |input: &[u8], output: &mut [u8]| {
+		encoding
+			.decode_mut(input, output)
+			.map_err(|partial| Error::from(partial.error))
+	};
+	let input = input.as_bytes();
+	if encoding.decode_len(input.len())? == output.len() {
+		return decode(input, output);
+	}
+	// A block is the least number of characters that encode whole bytes.
+	let bits = encoding.bit_width();
+	let common = 1 << bits.trailing_zeros().min(3);
+	let (block_chars, block_bytes) = (8 / common, bits / common);
+	if input.len() % block_chars != 0 {
+		return Err(Error::InvalidBaseString);
+	}
+	let head = input.len() - block_chars;
+	let head_bytes = head / block_chars * block_bytes;
+	decode(&input[..head], &mut output[..head_bytes])?;
+	let mut block = [0; 8];
+	let len = decode(&input[head..], &mut block[..block_bytes])?;
+	output
+		.get_mut(head_bytes..head_bytes + len)
+		.ok_or(Error::BufferTooSmall)?
+		.copy_from_slice(&block[..len]);
+	Ok(head_bytes + len)
+}
+
+/// Maps an error of a bit-block encoding to one with the position of the
+/// offending character.
+fn strict_error(input: &str, err: data_encoding::DecodeError) -> Error {
+	match err.kind {
+		DecodeKind::Symbol => invalid_character(input, err.position),
+		DecodeKind::Length => Error::InvalidLength,
+		DecodeKind::Trailing | DecodeKind::Padding => Error::NonCanonical {
+			position: err.position,
+		},
+	}
+}
+
+fn invalid_character(input: &str, position: usize) -> Error {
+	// Positions of the bit-block encodings are byte offsets, which may be in the
+	// middle of a multibyte character.
+	let start = (0..=position)
+		.rev()
+		.find(|start| input.is_char_boundary(*start))
+		.unwrap_or(0);
+	match input[start..].chars().next() {
+		Some(character) => Error::InvalidCharacter {
+			character,
+			position: start,
+		},
+		None => Error::InvalidBaseString,
+	}
+}
+
+/// Checks that the input is the canonical encoding. The decoding already
+/// checked padding and trailing bits, so only characters that were accepted
+/// in another case can differ.
+fn check_canonical(input: &str, canonical: &str) -> Result<()> {
+	match input
+		.bytes()
+		.zip(canonical.bytes())
+		.position(|(a, b)| a != b)
+	{
+		Some(position) => Err(invalid_character(input, position)),
+		None if input.len() != canonical.len() => Err(Error::NonCanonical {
+			position: input.len().min(canonical.len()),
+		}),
+		None => Ok(()),
+	}
+}
+
+/// Checks that every character is in the alphabet, in the same case.
+fn check_alphabet(alphabet: &str, input: &str) -> Result<()> {
+	match input.char_indices().find(|(_, c)| !alphabet.contains(*c)) {
+		Some((position, character)) => Err(Error::InvalidCharacter {
+			character,
+			position,
+		}),
+		None => Ok(()),
+	}
+}
+
+/// Decodes from an ASCII alphabet of any size, the same way `base_x` does,
+/// but using the output as scratch space instead of allocating.
+fn decode_base_x(
+	alphabet: &str,
+	input: &str,
+	output: &mut [u8],
+	ignore_case: bool,
+) -> Result<usize> {
+	let alphabet = alphabet.as_bytes();
+	let base = alphabet.len() as u32;
+	let value = |char: &u8| {
+		alphabet
+			.iter()
+			.position(|digit| {
+				digit == char || (ignore_case && digit.eq_ignore_ascii_case(char))
+			})
+			.ok_or(Error::InvalidBaseString)
+	};
+	let input = input.as_bytes();
+	// The bytes are accumulated in little-endian order.
+	let mut len = 0;
+	let zeros = input.iter().take_while(|char| value(char) == Ok(0)).count();
+	for char in &input[zeros..] {
+		let mut carry = value(char)? as u32;
+		for byte in &mut output[..len] {
+			carry += u32::from(*byte) * base;
+			*byte = carry as u8;
+			carry >>= 8;
+		}
+		while carry > 0 {
+			*output.get_mut(len).ok_or(Error::BufferTooSmall)? = carry as u8;
+			len += 1;
+			carry >>= 8;
+		}
+	}
+	// Every leading zero character is decoded as a zero byte.
+	for _ in 0..zeros {
+		*output.get_mut(len).ok_or(Error::BufferTooSmall)? = 0;
+		len += 1;
+	}
+	output[..len].reverse();
+	Ok(len)
+}
+
+/// Returns the maximum length of input of the given length encoded into an
+/// alphabet of any size.
+///
+/// Every character carries at least as many bits as the floor of the
+/// logarithm of the alphabet size.
+fn max_base_x_encoded_len(alphabet: &str, len: usize) -> usize {
+	let bits = alphabet.len().ilog2() as usize;
+	(len * 8 + bits - 1) / bits
+}
+
+/// The size of the stack buffer used to compute the exact lengths of the
+/// encodings into an alphabet of any size, which is plenty for a CID.
+const BASE_X_SCRATCH_LEN: usize = 256;
+
+/// Returns the exact length of the input encoded into an alphabet of any size,
+/// or the maximum length if the encoding doesn't fit in
+/// [`BASE_X_SCRATCH_LEN`] bytes.
+///
+/// Only the leading zeros are encoded one by one, the length of the rest
+/// depends on its value, so it's converted in scratch space.
+fn base_x_encoded_len(alphabet: &str, input: &[u8]) -> usize {
+	let mut output = [0; BASE_X_SCRATCH_LEN];
+	encode_base_x(alphabet, input, &mut output)
+		.unwrap_or_else(|_| max_base_x_encoded_len(alphabet, input.len()))
+}
+
+/// Returns the exact length of the input decoded from an alphabet of any size,
+/// or the maximum length if the decoded bytes don't fit in
+/// [`BASE_X_SCRATCH_LEN`] bytes, see [`base_x_encoded_len`].
+fn base_x_decoded_len(
+	alphabet: &str,
+	input: &str,
+	ignore_case: bool,
+) -> Result<usize> {
+	let mut output = [0; BASE_X_SCRATCH_LEN];
+	match decode_base_x(alphabet, input, &mut output, ignore_case) {
+		Err(Error::BufferTooSmall) => Ok(input.len()),
+		result => result,
+	}
 }
 
 /// Identity, 8-bit binary (encoder and decoder keeps data unmodified).
@@ -62,9 +379,37 @@ impl BaseCodec for Identity {
 			.expect("input must be valid UTF-8 bytes")
 	}
 
+	fn encode_to_slice(input: &[u8], output: &mut [u8]) -> Result<usize> {
+		output
+			.get_mut(..input.len())
+			.ok_or(Error::BufferTooSmall)?
+			.copy_from_slice(input);
+		Ok(input.len())
+	}
+
 	fn decode<I: AsRef<str>>(input: I) -> Result<Vec<u8>> {
 		Ok(input.as_ref().as_bytes().to_vec())
 	}
+
+	fn decode_strict(input: &str) -> Result<Vec<u8>> {
+		Self::decode(input)
+	}
+
+	fn decode_to_slice(input: &str, output: &mut [u8]) -> Result<usize> {
+		Self::encode_to_slice(input.as_bytes(), output)
+	}
+
+	fn max_encoded_len(len: usize) -> usize {
+		len
+	}
+
+	fn max_decoded_len(len: usize) -> usize {
+		len
+	}
+
+	fn decoded_len(input: &str) -> Result<usize> {
+		Ok(input.len())
+	}
 }
 
 /// Base256Emoji (alphabet:
@@ -77,9 +422,63 @@ impl BaseCodec for Base256Emoji {
 		Emoji::encode(input.as_ref())
 	}
 
+	fn encode_to_slice(input: &[u8], output: &mut [u8]) -> Result<usize> {
+		let mut written = 0;
+		for byte in input {
+			let emoji = Emoji::ALPHABET[usize::from(*byte)];
+			let output = output
+				.get_mut(written..written + emoji.len_utf8())
+				.ok_or(Error::BufferTooSmall)?;
+			written += emoji.encode_utf8(output).len();
+		}
+		Ok(written)
+	}
+
 	fn decode<I: AsRef<str>>(input: I) -> Result<Vec<u8>> {
 		Emoji::decode(input.as_ref()).map_err(|e| e.into())
 	}
+
+	fn decode_strict(input: &str) -> Result<Vec<u8>> {
+		input
+			.char_indices()
+			.map(|(position, character)| {
+				Emoji::get_index(character).ok_or(Error::InvalidCharacter {
+					character,
+					position,
+				})
+			})
+			.collect()
+	}
+
+	fn decode_to_slice(input: &str, output: &mut [u8]) -> Result<usize> {
+		let mut written = 0;
+		for char in input.chars() {
+			let byte = Emoji::get_index(char).ok_or(Error::InvalidBaseString)?;
+			*output.get_mut(written).ok_or(Error::BufferTooSmall)? = byte;
+			written += 1;
+		}
+		Ok(written)
+	}
+
+	fn max_encoded_len(len: usize) -> usize {
+		len * 4
+	}
+
+	fn max_decoded_len(len: usize) -> usize {
+		// Every emoji of the alphabet takes at least 3 bytes in UTF-8.
+		len / 3
+	}
+
+	fn encoded_len(input: &[u8]) -> usize {
+		input
+			.iter()
+			.map(|byte| Emoji::ALPHABET[usize::from(*byte)].len_utf8())
+			.sum()
+	}
+
+	fn decoded_len(input: &str) -> Result<usize> {
+		Ok(input.chars().count())
+	}
 }
 
 derive_base_encoding! {
@@ -138,11 +537,40 @@ impl BaseCodec for Base36Lower {
 		base_x::encode(encoding::BASE36_LOWER, input.as_ref())
 	}
 
+	fn encode_to_slice(input: &[u8], output: &mut [u8]) -> Result<usize> {
+		encode_base_x(encoding::BASE36_LOWER, input, output)
+	}
+
 	fn decode<I: AsRef<str>>(input: I) -> Result<Vec<u8>> {
 		// The input is case insensitive, hence lowercase it
 		let lowercased = input.as_ref().to_ascii_lowercase();
 		Ok(base_x::decode(encoding::BASE36_LOWER, &lowercased)?)
 	}
+
+	fn decode_strict(input: &str) -> Result<Vec<u8>> {
+		check_alphabet(encoding::BASE36_LOWER, input)?;
+		Ok(base_x::decode(encoding::BASE36_LOWER, input)?)
+	}
+
+	fn decode_to_slice(input: &str, output: &mut [u8]) -> Result<usize> {
+		decode_base_x(encoding::BASE36_LOWER, input, output, true)
+	}
+
+	fn max_encoded_len(len: usize) -> usize {
+		max_base_x_encoded_len(encoding::BASE36_LOWER, len)
+	}
+
+	fn max_decoded_len(len: usize) -> usize {
+		len
+	}
+
+	fn encoded_len(input: &[u8]) -> usize {
+		base_x_encoded_len(encoding::BASE36_LOWER, input)
+	}
+
+	fn decoded_len(input: &str) -> Result<usize> {
+		base_x_decoded_len(encoding::BASE36_LOWER, input, true)
+	}
 }
 
 /// Base36, [0-9A-Z] no padding (alphabet:
@@ -155,9 +583,275 @@ impl BaseCodec for Base36Upper {
 		base_x::encode(encoding::BASE36_UPPER, input.as_ref())
 	}
 
+	fn encode_to_slice(input: &[u8], output: &mut [u8]) -> Result<usize> {
+		encode_base_x(encoding::BASE36_UPPER, input, output)
+	}
+
 	fn decode<I: AsRef<str>>(input: I) -> Result<Vec<u8>> {
 		// The input is case insensitive, hence uppercase it
 		let uppercased = input.as_ref().to_ascii_uppercase();
 		Ok(base_x::decode(encoding::BASE36_UPPER, &uppercased)?)
 	}
+
+	fn decode_strict(input: &str) -> Result<Vec<u8>> {
+		check_alphabet(encoding::BASE36_UPPER, input)?;
+		Ok(base_x::decode(encoding::BASE36_UPPER, input)?)
+	}
+
+	fn decode_to_slice(input: &str, output: &mut [u8]) -> Result<usize> {
+		decode_base_x(encoding::BASE36_UPPER, input, output, true)
+	}
+
+	fn max_encoded_len(len: usize) -> usize {
+		max_base_x_encoded_len(encoding::BASE36_UPPER, len)
+	}
+
+	fn max_decoded_len(len: usize) -> usize {
+		len
+	}
+
+	fn encoded_len(input: &[u8]) -> usize {
+		base_x_encoded_len(encoding::BASE36_UPPER, input)
+	}
+
+	fn decoded_len(input: &str) -> Result<usize> {
+		base_x_decoded_len(encoding::BASE36_UPPER, input, true)
+	}
+}
+
+/// Encodes through the slice encoding of a codec that only produces ASCII.
+fn encode_ascii<C: BaseCodec>(input: &[u8]) -> String {
+	let mut output = alloc::vec![0; C::max_encoded_len(input.len())];
+	let len = C::encode_to_slice(input, &mut output)
+		.expect("output has the maximum encoded length");
+	output.truncate(len);
+	String::from_utf8(output).expect("encoding is ASCII")
+}
+
+/// Decodes through the slice decoding of a codec.
+fn decode_to_vec<C: BaseCodec>(input: &str) -> Result<Vec<u8>> {
+	let mut output = alloc::vec![0; C::max_decoded_len(input.len())];
+	let len = C::decode_to_slice(input, &mut output)?;
+	output.truncate(len);
+	Ok(output)
+}
+
+/// Writes the byte at the position and advances it.
+fn push(output: &mut [u8], written: &mut usize, byte: u8) -> Result<()> {
+	*output.get_mut(*written).ok_or(Error::BufferTooSmall)? = byte;
+	*written += 1;
+	Ok(())
+}
+
+/// Proquint, pronounceable words of 16 bits separated by dashes (consonants:
+/// bdfghjklmnprstvz, vowels: aiou).
+///
+/// The encoded string starts with `ro-`, so that the multibase string starts
+/// with `pro-`. An odd trailing byte is encoded as a word of 3 characters.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub(crate) struct Proquint;
+
+impl Proquint {
+	const PREFIX: &'static [u8] = b"ro-";
+}
+
+impl BaseCodec for Proquint {
+	fn encode<I: AsRef<[u8]>>(input: I) -> String {
+		encode_ascii::<Self>(input.as_ref())
+	}
+
+	fn encode_to_slice(input: &[u8], output: &mut [u8]) -> Result<usize> {
+		let consonants = encoding::PROQUINT_CONSONANTS.as_bytes();
+		let vowels = encoding::PROQUINT_VOWELS.as_bytes();
+		let mut written = 0;
+		for byte in Self::PREFIX {
+			push(output, &mut written, *byte)?;
+		}
+		for (i, chunk) in input.chunks(2).enumerate() {
+			if i > 0 {
+				push(output, &mut written, b'-')?;
+			}
+			let word =
+				u16::from(chunk[0]) << 8 | u16::from(*chunk.get(1).unwrap_or(&0));
+			let chars = [
+				consonants[usize::from(word >> 12)],
+				vowels[usize::from(word >> 10 & 3)],
+				consonants[usize::from(word >> 6 & 15)],
+				vowels[usize::from(word >> 4 & 3)],
+				consonants[usize::from(word & 15)],
+			];
+			// A single byte only needs the first 10 bits.
+			let len = if chunk.len() == 2 { 5 } else { 3 };
+			for char in &chars[..len] {
+				push(output, &mut written, *char)?;
+			}
+		}
+		Ok(written)
+	}
+
+	fn decode<I: AsRef<str>>(input: I) -> Result<Vec<u8>> {
+		decode_to_vec::<Self>(input.as_ref())
+	}
+
+	fn decode_strict(input: &str) -> Result<Vec<u8>> {
+		Self::decode(input)
+	}
+
+	fn decode_to_slice(input: &str, output: &mut [u8]) -> Result<usize> {
+		let consonants = encoding::PROQUINT_CONSONANTS.as_bytes();
+		let vowels = encoding::PROQUINT_VOWELS.as_bytes();
+		let bytes = input.as_bytes();
+		for (position, expected) in Self::PREFIX.iter().enumerate() {
+			match bytes.get(position) {
+				Some(byte) if byte == expected => {}
+				Some(_) => return Err(invalid_character(input, position)),
+				None => return Err(Error::InvalidLength),
+			}
+		}
+		let mut written = 0;
+		let mut position = Self::PREFIX.len();
+		if position == bytes.len() {
+			return Ok(0);
+		}
+		for word in bytes[position..].split(|byte| *byte == b'-') {
+			// Only the last word may encode a single byte.
+			let single = match word.len() {
+				5 => false,
+				3 if position + 3 == bytes.len() => true,
+				_ => return Err(Error::InvalidLength),
+			};
+			let mut value = 0u16;
+			for (i, char) in word.iter().enumerate() {
+				let (alphabet, bits) = if i % 2 == 0 {
+					(consonants, 4)
+				} else {
+					(vowels, 2)
+				};
+				let digit = alphabet
+					.iter()
+					.position(|digit| digit == char)
+					.ok_or_else(|| invalid_character(input, position + i))?;
+				value = value << bits | digit as u16;
+			}
+			if single {
+				// The last 2 bits of the last consonant are padding.
+				if value & 3 != 0 {
+					return Err(Error::NonCanonical {
+						position: position + 2,
+					});
+				}
+				push(output, &mut written, (value >> 2) as u8)?;
+			} else {
+				for byte in value.to_be_bytes() {
+					push(output, &mut written, byte)?;
+				}
+			}
+			position += word.len() + 1;
+		}
+		Ok(written)
+	}
+
+	fn max_encoded_len(len: usize) -> usize {
+		let words = (len + 1) / 2;
+		Self::PREFIX.len() + len / 2 * 5 + len % 2 * 3 + words.saturating_sub(1)
+	}
+
+	fn max_decoded_len(len: usize) -> usize {
+		// Every 2 bytes take a word and a dash, the last byte a short word.
+		(len.saturating_sub(Self::PREFIX.len()) + 1) / 3
+	}
+
+	fn decoded_len(input: &str) -> Result<usize> {
+		let len = input
+			.len()
+			.checked_sub(Self::PREFIX.len())
+			.ok_or(Error::InvalidLength)?;
+		// Words of 5 characters with a dash in between, the last one may have 3.
+		match (len + 1) % 6 {
+			0 => Ok((len + 1) / 6 * 2),
+			4 => Ok((len + 1) / 6 * 2 + 1),
+			_ if len == 0 => Ok(0),
+			_ => Err(Error::InvalidLength),
+		}
+	}
+}
+
+/// Base45, rfc9285 (alphabet: 0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:).
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub(crate) struct Base45;
+
+impl BaseCodec for Base45 {
+	fn encode<I: AsRef<[u8]>>(input: I) -> String {
+		encode_ascii::<Self>(input.as_ref())
+	}
+
+	fn encode_to_slice(input: &[u8], output: &mut [u8]) -> Result<usize> {
+		let alphabet = encoding::BASE45.as_bytes();
+		let mut written = 0;
+		for chunk in input.chunks(2) {
+			let mut value = chunk
+				.iter()
+				.fold(0, |value, byte| value << 8 | usize::from(*byte));
+			// Two bytes are encoded as 3 characters, a single byte as 2.
+			for _ in 0..chunk.len() + 1 {
+				push(output, &mut written, alphabet[value % 45])?;
+				value /= 45;
+			}
+		}
+		Ok(written)
+	}
+
+	fn decode<I: AsRef<str>>(input: I) -> Result<Vec<u8>> {
+		decode_to_vec::<Self>(input.as_ref())
+	}
+
+	fn decode_strict(input: &str) -> Result<Vec<u8>> {
+		Self::decode(input)
+	}
+
+	fn decode_to_slice(input: &str, output: &mut [u8]) -> Result<usize> {
+		let alphabet = encoding::BASE45.as_bytes();
+		let bytes = input.as_bytes();
+		if bytes.len() % 3 == 1 {
+			return Err(Error::InvalidLength);
+		}
+		let mut written = 0;
+		for (i, chunk) in bytes.chunks(3).enumerate() {
+			// The least significant character comes first.
+			let mut value = 0;
+			let mut weight = 1;
+			for (j, char) in chunk.iter().enumerate() {
+				let digit = alphabet
+					.iter()
+					.position(|digit| digit == char)
+					.ok_or_else(|| invalid_character(input, i * 3 + j))?;
+				value += digit * weight;
+				weight *= 45;
+			}
+			// 3 characters decode to 2 bytes, 2 characters to a single byte.
+			let len = chunk.len() - 1;
+			if value >> (8 * len) != 0 {
+				return Err(Error::InvalidBaseString);
+			}
+			for byte in &[(value >> 8) as u8, value as u8][2 - len..] {
+				push(output, &mut written, *byte)?;
+			}
+		}
+		Ok(written)
+	}
+
+	fn max_encoded_len(len: usize) -> usize {
+		len / 2 * 3 + len % 2 * 2
+	}
+
+	fn max_decoded_len(len: usize) -> usize {
+		len / 3 * 2 + usize::from(len % 3 == 2)
+	}
+
+	fn decoded_len(input: &str) -> Result<usize> {
+		if input.len() % 3 == 1 {
+			return Err(Error::InvalidLength);
+		}
+		Ok(Self::max_decoded_len(input.len()))
+	}
 }
@@ -0,0 +1,195 @@
+//! Multibase decoding that can be evaluated at compile time.
+//!
+//! Only the bases that CIDs and multihashes are commonly encoded with are
+//! supported.
+
+/// The maximum size of the decoded data.
+pub(crate) const MAX_DECODED_LEN: usize = 320;
+
+/// Decoded data, stored inline.
+pub(crate) struct Decoded {
+	bytes: [u8; MAX_DECODED_LEN],
+	len: usize,
+}
+
+impl Decoded {
+	/// Returns the decoded bytes.
+	pub(crate) const fn as_bytes(&self) -> &[u8] {
+		// `split_at` is only const since Rust 1.71.
+		let mut bytes: &[u8] = &self.bytes;
+		while bytes.len() > self.len {
+			let [rest @ .., _] = bytes else {
+				unreachable!()
+			};
+			bytes = rest;
+		}
+		bytes
+	}
+}
+
+const BASE16_LOWER: &[u8] = b"0123456789abcdef";
+const BASE16_UPPER: &[u8] = b"0123456789ABCDEF";
+const BASE32_LOWER: &[u8] = b"abcdefghijklmnopqrstuvwxyz234567";
+const BASE32_UPPER: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+const BASE36_LOWER: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+const BASE36_UPPER: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+pub(crate) const BASE58_BTC: &[u8] =
+	b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Decodes a multibase string.
+pub(crate) const fn decode(input: &str) -> Result<Decoded, &'static str> {
+	let [code, data @ ..] = input.as_bytes() else {
+		return Err("empty multibase string");
+	};
+	match *code {
+		b'f' => decode_bits(data, BASE16_LOWER, 4),
+		b'F' => decode_bits(data, BASE16_UPPER, 4),
+		b'b' => decode_bits(data, BASE32_LOWER, 5),
+		b'B' => decode_bits(data, BASE32_UPPER, 5),
+		b'k' => decode_base_x(data, BASE36_LOWER),
+		b'K' => decode_base_x(data, BASE36_UPPER),
+		b'z' => decode_base_x(data, BASE58_BTC),
+		_ => Err("unsupported multibase, use base16, base32, base36 or base58btc"),
+	}
+}
+
+/// Returns the value of the character in the alphabet.
+const fn position(alphabet: &[u8], char: u8) -> Option<u8> {
+	let mut i = 0;
+	while i < alphabet.len() {
+		if alphabet[i] == char {
+			return Some(i as u8);
+		}
+		i += 1;
+	}
+	None
+}
+
+/// Decodes a base whose characters each encode `bits` bits, without padding.
+const fn decode_bits(
+	input: &[u8],
+	alphabet: &[u8],
+	bits: u32,
+) -> Result<Decoded, &'static str> {
+	let mut decoded = Decoded {
+		bytes: [0; MAX_DECODED_LEN],
+		len: 0,
+	};
+	let mut buffer = 0u32;
+	let mut buffered = 0;
+	let mut i = 0;
+	while i < input.len() {
+		let value = match position(alphabet, input[i]) {
+			Some(value) => value,
+			None => return Err("invalid character in multibase string"),
+		};
+		buffer = (buffer << bits) | value as u32;
+		buffered += bits;
+		if buffered >= 8 {
+			buffered -= 8;
+			if decoded.len == MAX_DECODED_LEN {
+				return Err("multibase string is too long");
+			}
+			decoded.bytes[decoded.len] = (buffer >> buffered) as u8;
+			decoded.len += 1;
+			buffer &= (1 << buffered) - 1;
+		}
+		i += 1;
+	}
+	// The remaining bits only pad the last character.
+	if buffered >= bits || buffer != 0 {
+		return Err("invalid length of multibase string");
+	}
+	Ok(decoded)
+}
+
+/// Decodes a base that treats the input as a big number.
+pub(crate) const fn decode_base_x(
+	input: &[u8],
+	alphabet: &[u8],
+) -> Result<Decoded, &'static str> {
+	let mut decoded = Decoded {
+		bytes: [0; MAX_DECODED_LEN],
+		len: 0,
+	};
+	// The number is stored little-endian, it's reversed at the end.
+	let mut i = 0;
+	while i < input.len() {
+		let mut carry = match position(alphabet, input[i]) {
+			Some(value) => value as u32,
+			None => return Err("invalid character in multibase string"),
+		};
+		let mut j = 0;
+		while j < decoded.len {
+			carry += decoded.bytes[j] as u32 * alphabet.len() as u32;
+			decoded.bytes[j] = carry as u8;
+			carry >>= 8;
+			j += 1;
+		}
+		while carry > 0 {
+			if decoded.len == MAX_DECODED_LEN {
+				return Err("multibase string is too long");
+			}
+			decoded.bytes[decoded.len] = carry as u8;
+			decoded.len += 1;
+			carry >>= 8;
+		}
+		i += 1;
+	}
+	// Every leading zero character is a leading zero byte.
+	let mut i = 0;
+	while i < input.len() && input[i] == alphabet[0] {
+		if decoded.len == MAX_DECODED_LEN {
+			return Err("multibase string is too long");
+		}
+		decoded.bytes[decoded.len] = 0;
+		decoded.len += 1;
+		i += 1;
+	}
+	let mut j = 0;
+	while j < decoded.len / 2 {
+		let byte = decoded.bytes[j];
+		decoded.bytes[j] = decoded.bytes[decoded.len - 1 - j];
+		decoded.bytes[decoded.len - 1 - j] = byte;
+		j += 1;
+	}
+	Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::*,
+		crate::multibase::{self, Base},
+	};
+
+	#[test]
+	fn test_decode() {
+		let data = [0, 0, 1, 2, 3, 250, 251, 252, 0, 7];
+		for base in [
+			Base::Base16Lower,
+			Base::Base16Upper,
+			Base::Base32Lower,
+			Base::Base32Upper,
+			Base::Base36Lower,
+			Base::Base36Upper,
+			Base::Base58Btc,
+		] {
+			for len in 0..data.len() {
+				let encoded = multibase::encode(base, &data[..len]);
+				assert_eq!(decode(&encoded).unwrap().as_bytes(), &data[..len]);
+			}
+		}
+	}
+
+	#[test]
+	fn test_decode_invalid() {
+		assert!(decode("").is_err());
+		assert!(decode("mAQID").is_err());
+		assert!(decode("b!").is_err());
+		// Non-zero padding bits and too many trailing characters.
+		assert!(decode("bab").is_err());
+		assert!(decode("baea").is_err());
+		assert!(decode("f0").is_err());
+	}
+}
@@ -8,10 +8,13 @@ mod base;
 mod encoding;
 mod error;
 mod impls;
+pub(crate) mod literal;
+mod stream;
 
 pub use self::{
 	base::Base,
 	error::{Error, Result},
+	stream::{Decoder, Encoder},
 };
 
 /// Decode the base string.
@@ -30,7 +33,36 @@ pub fn decode<T: AsRef<str>>(input: T) -> Result<(Base, Vec<u8>)> {
 	let input = input.as_ref();
 	let code = input.chars().next().ok_or(Error::InvalidBaseString)?;
 	let base = Base::from_code(code)?;
-	let decoded = base.decode(&input[code.len_utf8()..])?;
+	let offset = code.len_utf8();
+	let decoded = base
+		.decode(&input[offset..])
+		.map_err(|err| err.offset(offset))?;
+	Ok((base, decoded))
+}
+
+/// Decode the base string, accepting only its canonical form.
+///
+/// See [`Base::decode_strict`], the error positions include the base code.
+///
+/// # Examples
+///
+/// ```
+/// use multibase::{decode_strict, Base};
+///
+/// let expected = (Base::Base16Lower, b"hello".to_vec());
+/// assert_eq!(decode_strict("f68656c6c6f").unwrap(), expected);
+///
+/// let err = decode_strict("f68656C6c6f").unwrap_err();
+/// assert_eq!(err.to_string(), "Invalid character 'C' at position 6");
+/// ```
+pub fn decode_strict<T: AsRef<str>>(input: T) -> Result<(Base, Vec<u8>)> {
+	let input = input.as_ref();
+	let code = input.chars().next().ok_or(Error::InvalidBaseString)?;
+	let base = Base::from_code(code)?;
+	let offset = code.len_utf8();
+	let decoded = base
+		.decode_strict(&input[offset..])
+		.map_err(|err| err.offset(offset))?;
 	Ok((base, decoded))
 }
 
@@ -49,3 +81,292 @@ pub fn encode<T: AsRef<[u8]>>(base: Base, input: T) -> String {
 	encoded.insert(0, base.code());
 	encoded
 }
+
+/// Encode the byte slice to a base string into the output, without
+/// allocating.
+///
+/// Returns the number of bytes written, including the base code.
+///
+/// # Examples
+///
+/// ```
+/// use multibase::{encode_to_slice, Base};
+///
+/// let mut buf = [0; 16];
+/// let len = encode_to_slice(Base::Base58Btc, b"hello", &mut buf).unwrap();
+/// assert_eq!(&buf[..len], b"zCn8eVZg");
+/// ```
+pub fn encode_to_slice(
+	base: Base,
+	input: &[u8],
+	output: &mut [u8],
+) -> Result<usize> {
+	let code = base.code();
+	let prefix = output
+		.get_mut(..code.len_utf8())
+		.ok_or(Error::BufferTooSmall)?;
+	let prefix_len = code.encode_utf8(prefix).len();
+	Ok(prefix_len + base.encode_to_slice(input, &mut output[prefix_len..])?)
+}
+
+/// Decode the base string into the output, without allocating.
+///
+/// Returns the base and the number of bytes written.
+///
+/// # Examples
+///
+/// ```
+/// use multibase::{decode_to_slice, Base};
+///
+/// let mut buf = [0; 16];
+/// let (base, len) = decode_to_slice("zCn8eVZg", &mut buf).unwrap();
+/// assert_eq!((base, &buf[..len]), (Base::Base58Btc, &b"hello"[..]));
+/// ```
+pub fn decode_to_slice<T: AsRef<str>>(
+	input: T,
+	output: &mut [u8],
+) -> Result<(Base, usize)> {
+	let input = input.as_ref();
+	let code = input.chars().next().ok_or(Error::InvalidBaseString)?;
+	let base = Base::from_code(code)?;
+	let len = base.decode_to_slice(&input[code.len_utf8()..], output)?;
+	Ok((base, len))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_to_slice() {
+		let data = [0, 0, 1, 2, 3, 250, 251, 252, 0, 7, 255, 255];
+		for code in "079fFbBcCvVtThkKRZzmMuUp🚀".chars() {
+			let base = Base::from_code(code).unwrap();
+			for len in 0..data.len() {
+				let data = &data[..len];
+				let encoded = base.encode(data);
+				let mut buf = [0; 128];
+				let max_len = base.max_encoded_len(len);
+				assert!(encoded.len() <= max_len, "{:?} {}", base, len);
+				let written = base.encode_to_slice(data, &mut buf[..max_len]).unwrap();
+				assert_eq!(&buf[..written], encoded.as_bytes());
+
+				assert_eq!(base.encoded_len(data), encoded.len(), "{:?} {}", base, len);
+
+				let max_len = base.max_decoded_len(encoded.len());
+				assert!(len <= max_len, "{:?} {}", base, len);
+				assert_eq!(base.decoded_len(&encoded), Ok(len), "{:?} {}", base, len);
+				let written =
+					base.decode_to_slice(&encoded, &mut buf[..max_len]).unwrap();
+				assert_eq!(&buf[..written], data);
+
+				let encoded = encode(base, data);
+				let written = encode_to_slice(base, data, &mut buf).unwrap();
+				assert_eq!(&buf[..written], encoded.as_bytes());
+				assert_eq!(decode_to_slice(&encoded, &mut buf).unwrap(), (base, len));
+				assert_eq!(&buf[..len], data);
+			}
+		}
+	}
+
+	#[test]
+	fn test_decode_strict() {
+		let data = [0, 0, 1, 2, 3, 250, 251, 252, 0, 7, 255, 255];
+		for code in "079fFbBcCvVtThkKRZzmMuUp🚀".chars() {
+			let base = Base::from_code(code).unwrap();
+			for len in 0..data.len() {
+				let encoded = encode(base, &data[..len]);
+				assert_eq!(
+					decode_strict(&encoded).unwrap(),
+					(base, data[..len].to_vec())
+				);
+			}
+		}
+
+		for (input, err) in [
+			// Wrong case.
+			("f68656C6c6f", Error::InvalidCharacter {
+				character: 'C',
+				position: 6,
+			}),
+			("bNBSWY3DP", Error::InvalidCharacter {
+				character: 'N',
+				position: 1,
+			}),
+			("kA2", Error::InvalidCharacter {
+				character: 'A',
+				position: 1,
+			}),
+			("K2a", Error::InvalidCharacter {
+				character: 'a',
+				position: 2,
+			}),
+			// Characters outside the alphabet.
+			("zCn8eVZ0", Error::InvalidCharacter {
+				character: '0',
+				position: 7,
+			}),
+			("mAQ!D", Error::InvalidCharacter {
+				character: '!',
+				position: 3,
+			}),
+			("🚀🚀x", Error::InvalidCharacter {
+				character: 'x',
+				position: 8,
+			}),
+			("bnbswy3é", Error::InvalidCharacter {
+				character: 'é',
+				position: 7,
+			}),
+			// Non-zero trailing bits and bad padding.
+			("baf", Error::NonCanonical { position: 2 }),
+			("mAR", Error::NonCanonical { position: 2 }),
+			("MA===", Error::NonCanonical { position: 2 }),
+			("ba", Error::InvalidLength),
+			("f6", Error::InvalidLength),
+		] {
+			assert_eq!(decode_strict(input), Err(err), "{}", input);
+		}
+
+		// The permissive decoding accepts some of them.
+		assert!(decode("f68656C6c6f").is_ok());
+		assert!(decode("kA2").is_ok());
+	}
+
+	#[test]
+	fn test_proquint() {
+		for (data, encoded) in [
+			(&[][..], "pro-"),
+			(&[127, 0, 0, 1][..], "pro-lusab-babad"),
+			(&[63, 84, 220, 193][..], "pro-gutih-tugad"),
+			(&[127][..], "pro-lus"),
+			(&[127, 0, 1][..], "pro-lusab-bah"),
+		] {
+			assert_eq!(encode(Base::Proquint, data), encoded);
+			assert_eq!(decode(encoded).unwrap(), (Base::Proquint, data.to_vec()));
+		}
+
+		for (input, err) in [
+			("pr", Error::InvalidLength),
+			("pra-", Error::InvalidCharacter {
+				character: 'a',
+				position: 2,
+			}),
+			("pro-lusab-", Error::InvalidLength),
+			("pro-lus-babad", Error::InvalidLength),
+			("pro-lusxb", Error::InvalidCharacter {
+				character: 'x',
+				position: 7,
+			}),
+			("pro-lul", Error::NonCanonical { position: 6 }),
+		] {
+			assert_eq!(decode(input), Err(err), "{}", input);
+		}
+	}
+
+	#[test]
+	fn test_base45() {
+		for (data, encoded) in [
+			(&b""[..], "R"),
+			(&b"AB"[..], "RBB8"),
+			(&b"Hello!!"[..], "R%69 VD92EX0"),
+			(&b"base-45"[..], "RUJCLQE7W581"),
+			(&b"ietf!"[..], "RQED8WEX0"),
+		] {
+			assert_eq!(encode(Base::Base45, data), encoded);
+			assert_eq!(decode(encoded).unwrap(), (Base::Base45, data.to_vec()));
+		}
+
+		for (input, err) in [
+			("RBB8A", Error::InvalidLength),
+			("Rbb8", Error::InvalidCharacter {
+				character: 'b',
+				position: 1,
+			}),
+			("RGGW", Error::InvalidBaseString),
+			("R::", Error::InvalidBaseString),
+		] {
+			assert_eq!(decode(input), Err(err), "{}", input);
+		}
+	}
+
+	#[test]
+	fn test_to_slice_errors() {
+		let mut buf = [0; 4];
+		assert_eq!(
+			Base::Base58Btc.encode_to_slice(b"hello", &mut buf),
+			Err(Error::BufferTooSmall)
+		);
+		assert_eq!(
+			Base::Base16Lower.decode_to_slice("68656c6c6f", &mut buf),
+			Err(Error::BufferTooSmall)
+		);
+		assert_eq!(
+			decode_to_slice("zCn8eVZg", &mut buf),
+			Err(Error::BufferTooSmall)
+		);
+		assert_eq!(
+			Base::Base58Btc.decode_to_slice("0", &mut buf),
+			Err(Error::InvalidBaseString)
+		);
+		assert_eq!(
+			Base::Base32Lower.decode_to_slice("a", &mut buf),
+			Err(Error::InvalidBaseString)
+		);
+		assert_eq!(
+			Base::Base64.decode_to_slice("AAAAA=", &mut buf),
+			Err(Error::InvalidBaseString)
+		);
+		// Base36 is case insensitive.
+		let len = Base::Base36Lower.decode_to_slice("A2", &mut buf).unwrap();
+		assert_eq!(&buf[..len], Base::Base36Lower.decode("a2").unwrap());
+		assert_eq!(Base::Base36Lower.decoded_len("A2"), Ok(len));
+	}
+
+	#[test]
+	fn test_exact_len() {
+		// The length of base-x encodings depends on the value.
+		assert_eq!(Base::Base58Btc.encoded_len(&[0, 0, 1]), 3);
+		assert_eq!(Base::Base58Btc.encoded_len(&[0, 0, 255]), 4);
+		assert_eq!(Base::Base58Btc.max_encoded_len(3), 5);
+		assert_eq!(Base::Base58Btc.decoded_len("112"), Ok(3));
+		assert_eq!(Base::Base58Btc.max_decoded_len(3), 3);
+		assert_eq!(
+			Base::Base58Btc.decoded_len("0"),
+			Err(Error::InvalidBaseString)
+		);
+		// Padding doesn't count.
+		assert_eq!(Base::Base64Pad.decoded_len("AA=="), Ok(1));
+		assert_eq!(Base::Base64Pad.max_decoded_len(4), 3);
+		// The exact length is enough to decode padded input.
+		let data = [0, 1, 2, 250, 251, 252, 255];
+		for code in "cCtTMU".chars() {
+			let base = Base::from_code(code).unwrap();
+			for len in 0..data.len() {
+				let encoded = base.encode(&data[..len]);
+				let mut buf = [0; 7];
+				let exact = base.decoded_len(&encoded).unwrap();
+				let written =
+					base.decode_to_slice(&encoded, &mut buf[..exact]).unwrap();
+				assert_eq!(&buf[..written], &data[..len], "{:?} {}", base, len);
+			}
+		}
+		// Past the scratch space the base-x lengths are the maximum.
+		let data = [255; 300];
+		let encoded = Base::Base58Btc.encode(data);
+		let max_len = Base::Base58Btc.max_encoded_len(data.len());
+		assert!(encoded.len() < max_len);
+		assert_eq!(Base::Base58Btc.encoded_len(&data), max_len);
+		assert_eq!(Base::Base58Btc.decoded_len(&encoded), Ok(encoded.len()));
+
+		for (base, input) in [
+			(Base::Base32Lower, "a"),
+			(Base::Base64Pad, "AA="),
+			(Base::Base45, "A"),
+			(Base::Proquint, "ro-ba"),
+			(Base::Proquint, "r"),
+		] {
+			assert!(base.decoded_len(input).is_err(), "{:?} {}", base, input);
+		}
+	}
+}
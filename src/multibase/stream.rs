@@ -0,0 +1,343 @@
+//! Streaming encoding and decoding for the block-aligned bases.
+//!
+//! Bases like base64 map a fixed number of bytes onto a fixed number of
+//! characters, so the data can be encoded and decoded chunk by chunk. Bases
+//! like base58 treat the whole input as one big number, they can't be
+//! streamed.
+
+use {
+	super::{
+		base::Base,
+		encoding,
+		error::{Error, Result},
+	},
+	core2::io,
+	data_encoding::Encoding,
+};
+
+/// The size of the buffers for encoded characters.
+const BUFFER_SIZE: usize = 1024;
+
+/// Returns the encoding of a block-aligned base.
+fn block_encoding(base: Base) -> Result<Encoding> {
+	Ok(match base {
+		Base::Base2 => encoding::BASE2,
+		Base::Base8 => encoding::BASE8,
+		Base::Base16Lower => encoding::BASE16_LOWER,
+		Base::Base16Upper => encoding::BASE16_UPPER,
+		Base::Base32Lower => encoding::BASE32_NOPAD_LOWER,
+		Base::Base32Upper => encoding::BASE32_NOPAD_UPPER,
+		Base::Base32PadLower => encoding::BASE32_PAD_LOWER,
+		Base::Base32PadUpper => encoding::BASE32_PAD_UPPER,
+		Base::Base32HexLower => encoding::BASE32HEX_NOPAD_LOWER,
+		Base::Base32HexUpper => encoding::BASE32HEX_NOPAD_UPPER,
+		Base::Base32HexPadLower => encoding::BASE32HEX_PAD_LOWER,
+		Base::Base32HexPadUpper => encoding::BASE32HEX_PAD_UPPER,
+		Base::Base32Z => encoding::BASE32Z,
+		Base::Base64 => encoding::BASE64_NOPAD,
+		Base::Base64Pad => encoding::BASE64_PAD,
+		Base::Base64Url => encoding::BASE64URL_NOPAD,
+		Base::Base64UrlPad => encoding::BASE64URL_PAD,
+		base => return Err(Error::NotStreamable(base)),
+	})
+}
+
+/// Returns the number of bytes and characters of a block.
+fn block_size(encoding: &Encoding) -> (usize, usize) {
+	let bits = encoding.bit_width();
+	// The least common multiple of 8 and the bit width.
+	let mut block = 8;
+	while block % bits != 0 {
+		block += 8;
+	}
+	(block / 8, block / bits)
+}
+
+fn invalid_data() -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, "invalid base string")
+}
+
+impl Base {
+	/// Returns whether the base can be encoded and decoded with [`Encoder`]
+	/// and [`Decoder`].
+	pub fn is_streamable(&self) -> bool {
+		block_encoding(*self).is_ok()
+	}
+}
+
+/// Encodes everything that is written to it and writes the characters into
+/// the inner writer.
+///
+/// The multibase prefix isn't written, the output is the same as from
+/// [`Base::encode`]. The last block is only written by [`Encoder::finish`],
+/// which needs to be called when all the data is written.
+#[derive(Debug)]
+pub struct Encoder<W> {
+	writer: W,
+	encoding: Encoding,
+	input: [u8; BUFFER_SIZE],
+	input_len: usize,
+	chunk_len: usize,
+}
+
+impl<W: io::Write> Encoder<W> {
+	/// Creates an encoder for the base.
+	///
+	/// Fails with [`Error::NotStreamable`] if the base isn't block-aligned.
+	pub fn new(base: Base, writer: W) -> Result<Self> {
+		let encoding = block_encoding(base)?;
+		let (bytes, chars) = block_size(&encoding);
+		Ok(Self {
+			writer,
+			encoding,
+			input: [0; BUFFER_SIZE],
+			input_len: 0,
+			chunk_len: BUFFER_SIZE / chars * bytes,
+		})
+	}
+
+	/// Returns a reference to the inner writer.
+	pub fn get_ref(&self) -> &W {
+		&self.writer
+	}
+
+	/// Encodes the buffered input and writes it to the inner writer.
+	fn write_input(&mut self) -> io::Result<()> {
+		let mut output = [0; BUFFER_SIZE];
+		let len = self.encoding.encode_len(self.input_len);
+		self
+			.encoding
+			.encode_mut(&self.input[..self.input_len], &mut output[..len]);
+		self.input_len = 0;
+		self.writer.write_all(&output[..len])
+	}
+
+	/// Writes the last block, including padding, and returns the inner writer.
+	pub fn finish(mut self) -> io::Result<W> {
+		self.write_input()?;
+		self.writer.flush()?;
+		Ok(self.writer)
+	}
+}
+
+impl<W: io::Write> io::Write for Encoder<W> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		if self.input_len == self.chunk_len {
+			self.write_input()?;
+		}
+		let len = buf.len().min(self.chunk_len - self.input_len);
+		self.input[self.input_len..self.input_len + len]
+			.copy_from_slice(&buf[..len]);
+		self.input_len += len;
+		Ok(len)
+	}
+
+	/// Flushes the inner writer, an incomplete block stays buffered until
+	/// [`Encoder::finish`].
+	fn flush(&mut self) -> io::Result<()> {
+		let (bytes, _) = block_size(&self.encoding);
+		let complete = self.input_len - self.input_len % bytes;
+		if complete > 0 {
+			let mut output = [0; BUFFER_SIZE];
+			let len = self.encoding.encode_len(complete);
+			self
+				.encoding
+				.encode_mut(&self.input[..complete], &mut output[..len]);
+			self.writer.write_all(&output[..len])?;
+			self.input.copy_within(complete..self.input_len, 0);
+			self.input_len -= complete;
+		}
+		self.writer.flush()
+	}
+}
+
+/// Decodes the characters that are read from the inner reader.
+///
+/// The input must not have a multibase prefix, the same as for
+/// [`Base::decode`]. Invalid input fails with
+/// [`io::ErrorKind::InvalidData`].
+#[derive(Debug)]
+pub struct Decoder<R> {
+	reader: R,
+	encoding: Encoding,
+	block_chars: usize,
+	input: [u8; BUFFER_SIZE],
+	input_len: usize,
+	output: [u8; BUFFER_SIZE],
+	output_pos: usize,
+	output_len: usize,
+	eof: bool,
+}
+
+impl<R: io::Read> Decoder<R> {
+	/// Creates a decoder for the base.
+	///
+	/// Fails with [`Error::NotStreamable`] if the base isn't block-aligned.
+	pub fn new(base: Base, reader: R) -> Result<Self> {
+		let encoding = block_encoding(base)?;
+		let (_, block_chars) = block_size(&encoding);
+		Ok(Self {
+			reader,
+			encoding,
+			block_chars,
+			input: [0; BUFFER_SIZE],
+			input_len: 0,
+			output: [0; BUFFER_SIZE],
+			output_pos: 0,
+			output_len: 0,
+			eof: false,
+		})
+	}
+
+	/// Returns a reference to the inner reader.
+	pub fn get_ref(&self) -> &R {
+		&self.reader
+	}
+
+	/// Returns the inner reader.
+	pub fn into_inner(self) -> R {
+		self.reader
+	}
+
+	/// Reads from the inner reader and decodes all the complete blocks.
+	fn fill_output(&mut self) -> io::Result<()> {
+		loop {
+			match self.reader.read(&mut self.input[self.input_len..]) {
+				Ok(0) => self.eof = true,
+				Ok(len) => self.input_len += len,
+				Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+				Err(err) => return Err(err),
+			}
+			// The last block may be incomplete if there is no padding.
+			let len = if self.eof {
+				self.input_len
+			} else {
+				self.input_len - self.input_len % self.block_chars
+			};
+			if len == 0 && !self.eof {
+				continue;
+			}
+			let input = &self.input[..len];
+			let output_len =
+				self.encoding.decode_len(len).map_err(|_| invalid_data())?;
+			self.output_len = self
+				.encoding
+				.decode_mut(input, &mut self.output[..output_len])
+				.map_err(|_| invalid_data())?;
+			self.output_pos = 0;
+			self.input.copy_within(len..self.input_len, 0);
+			self.input_len -= len;
+			return Ok(());
+		}
+	}
+}
+
+impl<R: io::Read> io::Read for Decoder<R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		while self.output_pos == self.output_len {
+			if self.eof {
+				return Ok(0);
+			}
+			self.fill_output()?;
+		}
+		let len = buf.len().min(self.output_len - self.output_pos);
+		buf[..len]
+			.copy_from_slice(&self.output[self.output_pos..self.output_pos + len]);
+		self.output_pos += len;
+		Ok(len)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::*,
+		alloc::vec::Vec,
+		core2::io::{Read, Write},
+	};
+
+	const STREAMABLE: [Base; 17] = [
+		Base::Base2,
+		Base::Base8,
+		Base::Base16Lower,
+		Base::Base16Upper,
+		Base::Base32Lower,
+		Base::Base32Upper,
+		Base::Base32PadLower,
+		Base::Base32PadUpper,
+		Base::Base32HexLower,
+		Base::Base32HexUpper,
+		Base::Base32HexPadLower,
+		Base::Base32HexPadUpper,
+		Base::Base32Z,
+		Base::Base64,
+		Base::Base64Pad,
+		Base::Base64Url,
+		Base::Base64UrlPad,
+	];
+
+	/// Reads at most a few bytes at a time.
+	struct Trickle<'a>(&'a [u8], usize);
+
+	impl Read for Trickle<'_> {
+		fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+			let len = buf.len().min(self.1).min(self.0.len());
+			buf[..len].copy_from_slice(&self.0[..len]);
+			self.0 = &self.0[len..];
+			Ok(len)
+		}
+	}
+
+	#[test]
+	fn test_stream_roundtrip() {
+		let data: Vec<u8> = (0..3000u32).map(|i| (i * 7 % 251) as u8).collect();
+		for base in STREAMABLE {
+			assert!(base.is_streamable());
+			for len in [0, 1, 2, 3, 4, 5, 767, 768, 769, data.len()] {
+				let data = &data[..len];
+				let expected = base.encode(data);
+
+				let mut encoder = Encoder::new(base, Vec::new()).unwrap();
+				for chunk in data.chunks(7) {
+					encoder.write_all(chunk).unwrap();
+				}
+				encoder.flush().unwrap();
+				let encoded = encoder.finish().unwrap();
+				assert_eq!(encoded, expected.as_bytes(), "{:?} {}", base, len);
+
+				for step in [1, 3, BUFFER_SIZE] {
+					let reader = Trickle(expected.as_bytes(), step);
+					let mut decoder = Decoder::new(base, reader).unwrap();
+					let mut decoded = Vec::new();
+					decoder.read_to_end(&mut decoded).unwrap();
+					assert_eq!(decoded, data, "{:?} {} {}", base, len, step);
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn test_stream_invalid() {
+		for base in [
+			Base::Identity,
+			Base::Base10,
+			Base::Base36Lower,
+			Base::Base58Btc,
+			Base::Base256Emoji,
+		] {
+			assert!(!base.is_streamable());
+			assert_eq!(
+				Encoder::new(base, Vec::new()).unwrap_err(),
+				Error::NotStreamable(base)
+			);
+			assert!(Decoder::new(base, &b""[..]).is_err());
+		}
+
+		for input in ["mzxw6!", "mzx", "mzxw6=="] {
+			let mut decoder =
+				Decoder::new(Base::Base32Lower, input.as_bytes()).unwrap();
+			let err = decoder.read_to_end(&mut Vec::new()).unwrap_err();
+			assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{}", input);
+		}
+	}
+}
@@ -0,0 +1,269 @@
+//! The multicodec table.
+//!
+//! Multicodec is a self-describing table of codes, which are used throughout
+//! the multiformats, e.g. as the codec of a [`crate::Cid`] or as the hash
+//! function of a [`crate::Multihash`]. Each code has a name and a tag, which
+//! groups the codes by their purpose.
+//!
+//! The entries are generated from the [official table] by the build script.
+//! `src/multicodec/table.csv` is a verbatim copy of its `table.csv`, with 534
+//! entries and the SHA-256 checksum
+//! `611f20ff67d46d449091e4deb499aa95414fdd12d257ffbf0e8c41e7706b101d`. To
+//! update the table, replace the file with a newer revision.
+//!
+//! ```
+//! use ipld_nostd::multicodec::{Multicodec, Tag};
+//!
+//! let codec = Multicodec::from_code(0x71).unwrap();
+//! assert_eq!(codec, Multicodec::DagCbor);
+//! assert_eq!(codec.name(), "dag-cbor");
+//! assert_eq!(codec.tag(), Tag::Ipld);
+//! assert_eq!(Multicodec::from_name("sha2-256").unwrap().code(), 0x12);
+//! ```
+//!
+//! [official table]: https://github.com/multiformats/multicodec/blob/master/table.csv
+
+use core::fmt;
+
+/// The tag of a multicodec, which groups the codes by their purpose.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Tag {
+	/// CID versions.
+	Cid,
+	/// Filecoin specific codes.
+	Filecoin,
+	/// Non-cryptographic hash functions.
+	Hash,
+	/// IPLD codecs.
+	Ipld,
+	/// Encryption schemes.
+	Encryption,
+	/// Holochain specific codes.
+	Holochain,
+	/// Public and private keys.
+	Key,
+	/// libp2p specific codes.
+	Libp2p,
+	/// Multiaddr protocols.
+	Multiaddr,
+	/// The multiformats themselves.
+	Multiformat,
+	/// Multihash functions.
+	Multihash,
+	/// Namespaces, e.g. of paths.
+	Namespace,
+	/// Serialization formats, that are not IPLD codecs.
+	Serialization,
+	/// Similarity hashes.
+	Softhash,
+	/// Transport protocols.
+	Transport,
+	/// Signature algorithms of varsig.
+	Varsig,
+	/// 0xcert specific codes.
+	Zeroxcert,
+}
+
+impl Tag {
+	/// Returns the name of the tag, as used in the multicodec table.
+	pub const fn name(&self) -> &'static str {
+		match self {
+			Self::Cid => "cid",
+			Self::Encryption => "encryption",
+			Self::Filecoin => "filecoin",
+			Self::Hash => "hash",
+			Self::Holochain => "holochain",
+			Self::Ipld => "ipld",
+			Self::Key => "key",
+			Self::Libp2p => "libp2p",
+			Self::Multiaddr => "multiaddr",
+			Self::Multiformat => "multiformat",
+			Self::Multihash => "multihash",
+			Self::Namespace => "namespace",
+			Self::Serialization => "serialization",
+			Self::Softhash => "softhash",
+			Self::Transport => "transport",
+			Self::Varsig => "varsig",
+			Self::Zeroxcert => "zeroxcert",
+		}
+	}
+}
+
+impl fmt::Display for Tag {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+/// The status of a multicodec.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Status {
+	/// The code is stable and won't change.
+	Permanent,
+	/// The code may still change.
+	Draft,
+	/// The code shouldn't be used anymore.
+	Deprecated,
+}
+
+macro_rules! build_multicodec_enum {
+	( $(
+		$doc:literal, $variant:ident, $name:literal, $tag:ident, $status:ident,
+		$code:literal;
+	)* ) => {
+		/// An entry of the multicodec table.
+		#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+		#[non_exhaustive]
+		pub enum Multicodec {
+			$(
+				#[doc = $doc]
+				$variant,
+			)*
+		}
+
+		impl Multicodec {
+			/// All entries of the table, ordered by their code.
+			pub const ALL: &'static [Self] = &[$( Self::$variant, )*];
+
+			/// Returns the entry with the given code.
+			pub const fn from_code(code: u64) -> Option<Self> {
+				match code {
+					$( $code => Some(Self::$variant), )*
+					_ => None,
+				}
+			}
+
+			/// Returns the entry with the given name.
+			pub fn from_name(name: &str) -> Option<Self> {
+				match name {
+					$( $name => Some(Self::$variant), )*
+					_ => None,
+				}
+			}
+
+			/// Returns the code.
+			pub const fn code(&self) -> u64 {
+				match self {
+					$( Self::$variant => $code, )*
+				}
+			}
+
+			/// Returns the name.
+			pub const fn name(&self) -> &'static str {
+				match self {
+					$( Self::$variant => $name, )*
+				}
+			}
+
+			/// Returns the tag.
+			pub const fn tag(&self) -> Tag {
+				match self {
+					$( Self::$variant => Tag::$tag, )*
+				}
+			}
+
+			/// Returns the status.
+			pub const fn status(&self) -> Status {
+				match self {
+					$( Self::$variant => Status::$status, )*
+				}
+			}
+		}
+	};
+}
+
+include!(concat!(env!("OUT_DIR"), "/multicodec.rs"));
+
+impl fmt::Display for Multicodec {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+impl TryFrom<u64> for Multicodec {
+	type Error = u64;
+
+	/// Returns the unknown code as error.
+	fn try_from(code: u64) -> Result<Self, Self::Error> {
+		Self::from_code(code).ok_or(code)
+	}
+}
+
+impl From<Multicodec> for u64 {
+	fn from(multicodec: Multicodec) -> Self {
+		multicodec.code()
+	}
+}
+
+/// Formats a code by its name if it's in the table, else as hex number.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct CodeName(pub u64);
+
+impl fmt::Display for CodeName {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match Multicodec::from_code(self.0) {
+			Some(multicodec) => f.write_str(multicodec.name()),
+			None => write!(f, "{:#x}", self.0),
+		}
+	}
+}
+
+impl fmt::Debug for CodeName {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match Multicodec::from_code(self.0) {
+			Some(multicodec) => write!(f, "{} ({:#x})", multicodec.name(), self.0),
+			None => write!(f, "{:#x}", self.0),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, alloc::format};
+
+	#[test]
+	fn test_table_is_consistent() {
+		for (index, multicodec) in Multicodec::ALL.iter().enumerate() {
+			assert_eq!(Multicodec::from_code(multicodec.code()), Some(*multicodec));
+			assert_eq!(Multicodec::from_name(multicodec.name()), Some(*multicodec));
+			if let Some(previous) = index.checked_sub(1) {
+				assert!(Multicodec::ALL[previous].code() < multicodec.code());
+			}
+		}
+	}
+
+	#[test]
+	fn test_whole_table() {
+		assert_eq!(Multicodec::ALL.len(), 534);
+		assert_eq!(Multicodec::ALL[0], Multicodec::Identity);
+		let last = Multicodec::ALL[Multicodec::ALL.len() - 1];
+		assert_eq!(last.code(), 0xd0_2000);
+
+		let codec = Multicodec::from_name("bls12_381-g1-pub").unwrap();
+		assert_eq!(codec, Multicodec::Bls12_381G1Pub);
+		assert_eq!(codec.tag(), Tag::Key);
+		assert_eq!(Multicodec::DagCbor.status(), Status::Permanent);
+		assert_eq!(Multicodec::Cidv2.status(), Status::Draft);
+		assert_eq!(Multicodec::P2pWebrtcStar.status(), Status::Deprecated);
+	}
+
+	#[test]
+	fn test_lookups() {
+		assert_eq!(Multicodec::from_code(0x70), Some(Multicodec::DagPb));
+		assert_eq!(Multicodec::from_code(0x12).unwrap().tag(), Tag::Multihash);
+		assert_eq!(Multicodec::from_name("ed25519-pub").unwrap().code(), 0xed);
+		assert_eq!(Multicodec::from_code(0x3fff_ffff), None);
+		assert_eq!(Multicodec::from_name("dag-cbor2"), None);
+		assert_eq!(Multicodec::try_from(0x1e), Ok(Multicodec::Blake3));
+		assert_eq!(Multicodec::try_from(0x3fff_ffff), Err(0x3fff_ffff));
+	}
+
+	#[test]
+	fn test_display() {
+		assert_eq!(format!("{}", Multicodec::DagCbor), "dag-cbor");
+		assert_eq!(format!("{}", Tag::Multiaddr), "multiaddr");
+		assert_eq!(format!("{}", CodeName(0x71)), "dag-cbor");
+		assert_eq!(format!("{:?}", CodeName(0x71)), "dag-cbor (0x71)");
+		assert_eq!(format!("{}", CodeName(0x3fff_ffff)), "0x3fffffff");
+	}
+}
@@ -0,0 +1,178 @@
+use {
+	super::{error, Error, Multihash},
+	crate::varint::{decode, encode as varint_encode},
+	core::{cmp::Ordering, hash},
+	core2::io,
+};
+
+/// A multihash that borrows its digest from the bytes it was parsed from.
+///
+/// It compares and hashes the same way as [`Multihash`], so both can be used
+/// interchangeably as keys.
+#[derive(Clone, Copy, Debug, Eq)]
+pub struct MultihashRef<'a> {
+	/// The code of the Multihash.
+	code: u64,
+	/// The digest.
+	digest: &'a [u8],
+}
+
+impl<'a> MultihashRef<'a> {
+	/// Parses a multihash from bytes, without copying the digest.
+	///
+	/// The bytes need to contain exactly one multihash.
+	pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Error> {
+		let (multihash, remaining) = Self::read(bytes)?;
+		// There were more bytes supplied than read
+		if !remaining.is_empty() {
+			return Err(Error::invalid_size(remaining.len() as u64));
+		}
+		Ok(multihash)
+	}
+
+	/// Parses a multihash from the start of the bytes, without copying the
+	/// digest.
+	///
+	/// Returns the multihash and the bytes after it.
+	pub fn read(bytes: &'a [u8]) -> Result<(Self, &'a [u8]), Error> {
+		let (code, bytes) =
+			decode::u64(bytes).map_err(error::varint_decode_to_multihash_error)?;
+		let (size, bytes) =
+			decode::u64(bytes).map_err(error::varint_decode_to_multihash_error)?;
+		if size > u8::MAX as u64 {
+			return Err(Error::invalid_size(size));
+		}
+		if bytes.len() < size as usize {
+			return Err(error::io_to_multihash_error(
+				io::ErrorKind::UnexpectedEof.into(),
+			));
+		}
+		let (digest, bytes) = bytes.split_at(size as usize);
+		Ok((Self { code, digest }, bytes))
+	}
+
+	/// Returns the code of the multihash.
+	pub const fn code(&self) -> u64 {
+		self.code
+	}
+
+	/// Returns the size of the digest.
+	pub const fn size(&self) -> u8 {
+		self.digest.len() as u8
+	}
+
+	/// Returns the digest.
+	pub const fn digest(&self) -> &'a [u8] {
+		self.digest
+	}
+
+	/// Returns the length in bytes of the encoded multihash.
+	pub fn encoded_len(&self) -> usize {
+		let mut code_buf = varint_encode::u64_buffer();
+		let code = varint_encode::u64(self.code, &mut code_buf);
+
+		let mut size_buf = varint_encode::u8_buffer();
+		let size = varint_encode::u8(self.size(), &mut size_buf);
+
+		code.len() + size.len() + self.digest.len()
+	}
+
+	/// Copies the digest into an owned multihash.
+	///
+	/// This fails if the digest is larger than the allocated size `S`.
+	pub fn to_multihash<const S: usize>(&self) -> Result<Multihash<S>, Error> {
+		Multihash::wrap(self.code, self.digest)
+	}
+}
+
+impl<'a, const S: usize> From<&'a Multihash<S>> for MultihashRef<'a> {
+	fn from(multihash: &'a Multihash<S>) -> Self {
+		Self {
+			code: multihash.code(),
+			digest: multihash.digest(),
+		}
+	}
+}
+
+impl<const S: usize> TryFrom<MultihashRef<'_>> for Multihash<S> {
+	type Error = Error;
+
+	fn try_from(multihash: MultihashRef<'_>) -> Result<Self, Self::Error> {
+		multihash.to_multihash()
+	}
+}
+
+impl PartialEq for MultihashRef<'_> {
+	fn eq(&self, other: &Self) -> bool {
+		self.code == other.code && self.digest == other.digest
+	}
+}
+
+impl<const S: usize> PartialEq<Multihash<S>> for MultihashRef<'_> {
+	fn eq(&self, other: &Multihash<S>) -> bool {
+		self.code == other.code() && self.digest == other.digest()
+	}
+}
+
+// Same as for `Multihash`, only the actual digest is hashed.
+impl hash::Hash for MultihashRef<'_> {
+	fn hash<T: hash::Hasher>(&self, state: &mut T) {
+		self.code.hash(state);
+		self.digest.hash(state);
+	}
+}
+
+impl PartialOrd for MultihashRef<'_> {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+// The same order as `Multihash`, which compares the code, then the size and
+// then the digest.
+impl Ord for MultihashRef<'_> {
+	fn cmp(&self, other: &Self) -> Ordering {
+		(self.code, self.size(), self.digest).cmp(&(
+			other.code,
+			other.size(),
+			other.digest,
+		))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_multihash_ref() {
+		let multihash = Multihash::<64>::wrap(0x12, &[7; 32]).unwrap();
+		let bytes = multihash.to_bytes();
+		let multihash_ref = MultihashRef::from_bytes(&bytes).unwrap();
+		assert_eq!(multihash_ref.code(), 0x12);
+		assert_eq!(multihash_ref.size(), 32);
+		assert_eq!(multihash_ref.encoded_len(), bytes.len());
+		assert_eq!(multihash_ref, multihash);
+		assert_eq!(MultihashRef::from(&multihash), multihash_ref);
+		assert_eq!(multihash_ref.to_multihash::<64>().unwrap(), multihash);
+		assert!(multihash_ref.to_multihash::<16>().is_err());
+
+		assert!(MultihashRef::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+		let mut trailing = bytes.clone();
+		trailing.push(0);
+		assert!(MultihashRef::from_bytes(&trailing).is_err());
+		let (read, remaining) = MultihashRef::read(&trailing).unwrap();
+		assert_eq!(read, multihash);
+		assert_eq!(remaining, [0]);
+	}
+
+	#[test]
+	fn test_multihash_ref_ord() {
+		let short = Multihash::<64>::wrap(0x12, &[9]).unwrap();
+		let long = Multihash::<64>::wrap(0x12, &[0, 0]).unwrap();
+		assert_eq!(
+			MultihashRef::from(&short).cmp(&MultihashRef::from(&long)),
+			short.cmp(&long)
+		);
+	}
+}
@@ -0,0 +1,223 @@
+use {
+	super::{error, BuiltinCodeTable, CodeTable, Error, Hasher, Multihash},
+	core2::io,
+};
+
+/// The size of the buffer [`Multihash::digest_reader`] reads into.
+pub const DEFAULT_BUFFER_SIZE: usize = 8 * 1024;
+
+/// Computes a multihash from input that is fed incrementally.
+///
+/// The builder keeps the code together with the hasher, so that the resulting
+/// multihash always has the code of the algorithm that produced it.
+#[derive(Clone, Debug)]
+pub struct MultihashBuilder<H> {
+	code: u64,
+	hasher: H,
+}
+
+impl MultihashBuilder<<BuiltinCodeTable as CodeTable>::Hasher> {
+	/// Creates a builder for a code of the [`BuiltinCodeTable`].
+	pub fn new(code: u64) -> Result<Self, Error> {
+		Self::with_table(&BuiltinCodeTable, code)
+	}
+}
+
+impl<H: Hasher> MultihashBuilder<H> {
+	/// Creates a builder for a code of the given table.
+	///
+	/// The identity code isn't supported, as it has no hasher.
+	pub fn with_table<T>(table: &T, code: u64) -> Result<Self, Error>
+	where
+		T: CodeTable<Hasher = H>,
+	{
+		let hasher = table.hasher(code).ok_or(Error::unknown_code(code))?;
+		Ok(Self::from_hasher(code, hasher))
+	}
+
+	/// Creates a builder from a hasher, which needs to implement the hash
+	/// function of the code.
+	pub const fn from_hasher(code: u64, hasher: H) -> Self {
+		Self { code, hasher }
+	}
+
+	/// Returns the code of the multihash.
+	pub const fn code(&self) -> u64 {
+		self.code
+	}
+
+	/// Consumes the next part of the input.
+	pub fn update(&mut self, input: &[u8]) {
+		self.hasher.update(input);
+	}
+
+	/// Consumes all the input from the reader, returns the number of bytes that
+	/// were read.
+	///
+	/// The reader is read in chunks of the size of the given buffer.
+	pub fn update_reader<R: io::Read>(
+		&mut self,
+		mut reader: R,
+		buffer: &mut [u8],
+	) -> Result<u64, Error> {
+		if buffer.is_empty() {
+			return Err(error::io_to_multihash_error(
+				io::ErrorKind::InvalidInput.into(),
+			));
+		}
+		let mut total = 0;
+		loop {
+			match reader.read(buffer) {
+				Ok(0) => return Ok(total),
+				Ok(read) => {
+					self.hasher.update(&buffer[..read]);
+					total += read as u64;
+				}
+				Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+				Err(err) => return Err(error::io_to_multihash_error(err)),
+			}
+		}
+	}
+
+	/// Returns the multihash of the input that was consumed so far.
+	///
+	/// This fails if the digest is larger than the allocated size `S`.
+	pub fn finalize<const S: usize>(&mut self) -> Result<Multihash<S>, Error> {
+		Multihash::wrap(self.code, self.hasher.finalize())
+	}
+
+	/// Resets the builder, so that it can be used for a new input.
+	pub fn reset(&mut self) {
+		self.hasher.reset();
+	}
+}
+
+impl<const S: usize> Multihash<S> {
+	/// Computes the multihash of all the input of the reader, with a hash
+	/// function of the [`BuiltinCodeTable`].
+	///
+	/// The input is read in chunks of [`DEFAULT_BUFFER_SIZE`] bytes, use
+	/// [`Multihash::digest_reader_with`] for other buffer sizes or tables.
+	pub fn digest_reader<R: io::Read>(
+		code: u64,
+		reader: R,
+	) -> Result<Self, Error> {
+		let mut buffer = [0; DEFAULT_BUFFER_SIZE];
+		Self::digest_reader_with(&BuiltinCodeTable, code, reader, &mut buffer)
+	}
+
+	/// Computes the multihash of all the input of the reader, with the hash
+	/// function from the given table.
+	///
+	/// The input is read in chunks of the size of the given buffer.
+	pub fn digest_reader_with<T: CodeTable, R: io::Read>(
+		table: &T,
+		code: u64,
+		reader: R,
+		buffer: &mut [u8],
+	) -> Result<Self, Error> {
+		let mut builder = MultihashBuilder::with_table(table, code)?;
+		builder.update_reader(reader, buffer)?;
+		builder.finalize()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Hashes everything to the length of the input.
+	#[derive(Default)]
+	struct LenHasher([u8; 8]);
+
+	impl Hasher for LenHasher {
+		fn update(&mut self, input: &[u8]) {
+			let len = u64::from_be_bytes(self.0) + input.len() as u64;
+			self.0 = len.to_be_bytes();
+		}
+
+		fn finalize(&mut self) -> &[u8] {
+			&self.0
+		}
+
+		fn reset(&mut self) {
+			self.0 = [0; 8];
+		}
+	}
+
+	/// Returns at most three bytes per read and is interrupted in between.
+	struct ChunkedReader<'a> {
+		data: &'a [u8],
+		interrupt: bool,
+	}
+
+	impl io::Read for ChunkedReader<'_> {
+		fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+			self.interrupt = !self.interrupt;
+			if self.interrupt {
+				return Err(io::ErrorKind::Interrupted.into());
+			}
+			let len = buf.len().min(self.data.len()).min(3);
+			buf[..len].copy_from_slice(&self.data[..len]);
+			self.data = &self.data[len..];
+			Ok(len)
+		}
+	}
+
+	#[test]
+	fn test_builder() {
+		let table = BuiltinCodeTable.with_code(0x300000, LenHasher::default);
+		let mut builder = MultihashBuilder::with_table(&table, 0x300000).unwrap();
+		assert_eq!(builder.code(), 0x300000);
+		builder.update(b"hello");
+		builder.update(b" world");
+		let multihash: Multihash<8> = builder.finalize().unwrap();
+		assert_eq!(multihash.code(), 0x300000);
+		assert_eq!(multihash.digest(), 11u64.to_be_bytes());
+		assert!(builder.finalize::<4>().is_err());
+
+		builder.reset();
+		let reader = ChunkedReader {
+			data: &[0; 100],
+			interrupt: false,
+		};
+		let mut buffer = [0; 7];
+		assert_eq!(builder.update_reader(reader, &mut buffer).unwrap(), 100);
+		let multihash: Multihash<8> = builder.finalize().unwrap();
+		assert_eq!(multihash.digest(), 100u64.to_be_bytes());
+
+		assert!(builder.update_reader(&[1u8][..], &mut []).is_err());
+		assert!(MultihashBuilder::with_table(&table, 0x300001).is_err());
+	}
+
+	#[cfg(feature = "sha2")]
+	#[test]
+	fn test_digest_reader() {
+		use sha2::Digest;
+
+		let data = [7; 3 * DEFAULT_BUFFER_SIZE + 5];
+		let multihash = Multihash::<64>::digest_reader(0x12, &data[..]).unwrap();
+		assert_eq!(multihash.digest(), &sha2::Sha256::digest(data)[..]);
+
+		let mut buffer = [0; 5];
+		let reader = ChunkedReader {
+			data: &data,
+			interrupt: false,
+		};
+		let chunked = Multihash::<64>::digest_reader_with(
+			&BuiltinCodeTable,
+			0x12,
+			reader,
+			&mut buffer,
+		)
+		.unwrap();
+		assert_eq!(chunked, multihash);
+
+		let mut builder = MultihashBuilder::new(0x12).unwrap();
+		for chunk in data.chunks(1000) {
+			builder.update(chunk);
+		}
+		assert_eq!(builder.finalize::<64>().unwrap(), multihash);
+		assert!(MultihashBuilder::new(0x300000).is_err());
+	}
+}
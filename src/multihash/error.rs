@@ -1,5 +1,5 @@
 use {
-	crate::varint,
+	crate::{multicodec::CodeName, varint},
 	core2::{error::Error as StdError, io},
 	varint::decode,
 };
@@ -18,6 +18,12 @@ impl Error {
 		}
 	}
 
+	pub(crate) const fn unknown_code(code: u64) -> Self {
+		Self {
+			kind: Kind::UnknownCode(code),
+		}
+	}
+
 	pub(crate) const fn insufficient_varint_bytes() -> Self {
 		Self {
 			kind: Kind::Varint(decode::Error::Insufficient),
@@ -45,6 +51,8 @@ enum Kind {
 	InvalidSize(u64),
 	/// Invalid varint.
 	Varint(decode::Error),
+	/// No hash function for the code.
+	UnknownCode(u64),
 }
 
 pub(crate) fn varint_decode_to_multihash_error(
@@ -67,6 +75,9 @@ impl core::fmt::Display for Kind {
 			Self::Io(err) => write!(f, "{err}"),
 			Self::InvalidSize(size) => write!(f, "Invalid multihash size {size}."),
 			Self::Varint(err) => write!(f, "{err}"),
+			Self::UnknownCode(code) => {
+				write!(f, "Unknown multihash code {:?}.", CodeName(*code))
+			}
 		}
 	}
 }
@@ -77,6 +88,7 @@ impl StdError for Error {
 			Kind::Io(inner) => Some(inner),
 			Kind::InvalidSize(_) => None,
 			Kind::Varint(_) => None, // FIXME: Does not implement `core2::Error`.
+			Kind::UnknownCode(_) => None,
 		}
 	}
 }
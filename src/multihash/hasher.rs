@@ -0,0 +1,159 @@
+//! Hash functions that produce the digest of a multihash.
+
+/// A hash function that is fed with the input incrementally.
+pub trait Hasher {
+	/// Consumes the next part of the input.
+	fn update(&mut self, input: &[u8]);
+
+	/// Returns the digest of the input that was consumed so far.
+	///
+	/// It doesn't reset the state, call [`Hasher::reset`] for that.
+	fn finalize(&mut self) -> &[u8];
+
+	/// Returns the first `len` bytes of the digest, or `None` if the digest is
+	/// shorter.
+	///
+	/// Hash functions with an extendable output override it to return digests
+	/// that are longer than the one of [`Hasher::finalize`].
+	fn finalize_len(&mut self, len: usize) -> Option<&[u8]> {
+		self.finalize().get(..len)
+	}
+
+	/// Resets the state, so that the hasher can be used for a new input.
+	fn reset(&mut self);
+}
+
+macro_rules! build_sha2_hasher {
+	( $(#[$attr:meta] $name:ident($state:ty, $size:expr);)* ) => {
+		$(
+			#[$attr]
+			#[cfg(feature = "sha2")]
+			#[derive(Clone, Debug)]
+			pub struct $name {
+				state: $state,
+				digest: [u8; $size],
+			}
+
+			#[cfg(feature = "sha2")]
+			impl Default for $name {
+				fn default() -> Self {
+					Self {
+						state: Default::default(),
+						digest: [0; $size],
+					}
+				}
+			}
+
+			#[cfg(feature = "sha2")]
+			impl Hasher for $name {
+				fn update(&mut self, input: &[u8]) {
+					sha2::Digest::update(&mut self.state, input);
+				}
+
+				fn finalize(&mut self) -> &[u8] {
+					let digest = sha2::Digest::finalize(self.state.clone());
+					self.digest.copy_from_slice(&digest);
+					&self.digest
+				}
+
+				fn reset(&mut self) {
+					sha2::Digest::reset(&mut self.state);
+				}
+			}
+		)*
+	};
+}
+
+build_sha2_hasher! {
+	/// SHA2-256, needs the `sha2` feature.
+	Sha2_256(sha2::Sha256, 32);
+	/// SHA2-512, needs the `sha2` feature.
+	Sha2_512(sha2::Sha512, 64);
+}
+
+/// BLAKE3 with a digest of `S` bytes, needs the `blake3` feature.
+#[cfg(feature = "blake3")]
+#[derive(Clone, Debug)]
+pub struct Blake3<const S: usize> {
+	state: blake3::Hasher,
+	digest: [u8; S],
+}
+
+#[cfg(feature = "blake3")]
+impl<const S: usize> Default for Blake3<S> {
+	fn default() -> Self {
+		Self {
+			state: blake3::Hasher::new(),
+			digest: [0; S],
+		}
+	}
+}
+
+#[cfg(feature = "blake3")]
+impl<const S: usize> Hasher for Blake3<S> {
+	fn update(&mut self, input: &[u8]) {
+		self.state.update(input);
+	}
+
+	fn finalize(&mut self) -> &[u8] {
+		self.state.finalize_xof().fill(&mut self.digest);
+		&self.digest
+	}
+
+	fn reset(&mut self) {
+		self.state.reset();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[allow(dead_code)]
+	fn assert_streaming<H: Hasher + Default>(input: &[u8]) {
+		let mut whole = H::default();
+		whole.update(input);
+		let mut chunked = H::default();
+		for chunk in input.chunks(3) {
+			chunked.update(chunk);
+		}
+		assert_eq!(whole.finalize(), chunked.finalize());
+
+		let digest = whole.finalize().to_vec();
+		whole.update(b"more");
+		assert_ne!(whole.finalize(), digest);
+		whole.reset();
+		whole.update(input);
+		assert_eq!(whole.finalize(), digest);
+	}
+
+	#[cfg(feature = "sha2")]
+	#[test]
+	fn test_sha2() {
+		use sha2::Digest;
+
+		let mut hasher = Sha2_256::default();
+		hasher.update(b"hello");
+		assert_eq!(hasher.finalize(), &sha2::Sha256::digest(b"hello")[..]);
+		assert_streaming::<Sha2_256>(b"hello world");
+		assert_streaming::<Sha2_512>(b"hello world");
+	}
+
+	#[cfg(feature = "blake3")]
+	#[test]
+	fn test_blake3() {
+		let mut hasher = Blake3::<32>::default();
+		hasher.update(b"hello");
+		assert_eq!(hasher.finalize(), blake3::hash(b"hello").as_bytes());
+		assert_streaming::<Blake3<32>>(b"hello world");
+		assert_streaming::<Blake3<64>>(b"hello world");
+
+		let mut hasher = Blake3::<64>::default();
+		hasher.update(b"hello");
+		assert_eq!(
+			hasher.finalize_len(32),
+			Some(&blake3::hash(b"hello").as_bytes()[..])
+		);
+		assert_eq!(hasher.finalize_len(65), None);
+	}
+}
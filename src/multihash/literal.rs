@@ -0,0 +1,80 @@
+//! Support for the [`multihash!`](crate::multihash!) macro.
+
+use {super::Multihash, crate::multibase::literal as multibase};
+
+/// Parses a multibase encoded multihash, in a const context.
+pub const fn parse<const S: usize>(
+	input: &str,
+) -> Result<Multihash<S>, &'static str> {
+	let decoded = match multibase::decode(input) {
+		Ok(decoded) => decoded,
+		Err(err) => return Err(err),
+	};
+	Multihash::from_bytes_const(decoded.as_bytes())
+}
+
+/// Creates a multihash from a multibase encoded string at compile time.
+///
+/// The string needs to be encoded with base16, base32, base36 or base58btc, a
+/// malformed multihash fails the build. The allocated digest size defaults to
+/// 64 bytes, another one can be passed as second argument.
+///
+/// ```
+/// use ipld_nostd::{multihash, multihash::Multihash};
+///
+/// const HELLO: Multihash<8> = multihash!("f000568656c6c6f", 8);
+/// assert_eq!(HELLO.digest(), b"hello");
+/// ```
+#[macro_export]
+macro_rules! multihash {
+	($multihash:expr) => {
+		$crate::multihash!($multihash, 64)
+	};
+	($multihash:expr, $size:expr) => {{
+		const MULTIHASH: $crate::multihash::Multihash<$size> =
+			match $crate::multihash::literal::parse::<$size>($multihash) {
+				Ok(multihash) => multihash,
+				Err(err) => ::core::panic!("{}", err),
+			};
+		MULTIHASH
+	}};
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::multihash::Multihash;
+
+	const DIGEST: [u8; 32] = [
+		0xe3, 0xb0, 0xc4, 0x42, 0x98, 0xfc, 0x1c, 0x14, 0x9a, 0xfb, 0xf4, 0xc8,
+		0x99, 0x6f, 0xb9, 0x24, 0x27, 0xae, 0x41, 0xe4, 0x64, 0x9b, 0x93, 0x4c,
+		0xa4, 0x95, 0x99, 0x1b, 0x78, 0x52, 0xb8, 0x55,
+	];
+
+	const BASE58: Multihash<64> =
+		multihash!("zQmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n");
+	const BASE16: Multihash<32> = multihash!(
+		"f1220e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+		32
+	);
+
+	#[test]
+	fn test_multihash_literal() {
+		assert_eq!(BASE16.code(), 0x12);
+		assert_eq!(BASE16.digest(), DIGEST);
+		assert_eq!(BASE58.code(), 0x12);
+		assert_eq!(BASE58.size(), 32);
+	}
+
+	#[test]
+	fn test_multihash_literal_invalid() {
+		use super::parse;
+		assert!(parse::<64>("f1220e3b0").is_err());
+		assert!(
+			parse::<16>("zQmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n").is_err()
+		);
+		assert!(
+			parse::<64>("QmdfTbBqBPQ7VNxZEYEj14VmRuZBkqFbiwReogJgS1zR1n").is_err()
+		);
+		assert!(parse::<64>("f12").is_err());
+	}
+}
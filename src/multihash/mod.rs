@@ -3,12 +3,15 @@
 //! This crate defines a `no_std` compatible data structures for representing a
 //! `Multihash`.
 //!
-//! It does not offer any hashing, instead you are encouraged to either do the
-//! hashing yourself. Alternatively, you can use an existing code table or make
-//! your own code table.
+//! Hashing is done through a [`CodeTable`], which maps codes to [`Hasher`]s.
+//! The [`BuiltinCodeTable`] supports SHA2-256 and SHA2-512 via the `sha2`
+//! feature and BLAKE3 via the `blake3` feature. Custom
+//! codes can be added with [`CodeTable::with_code`]. Large inputs can be
+//! hashed incrementally with a [`MultihashBuilder`] or read from a reader with
+//! [`Multihash::digest_reader`].
 //!
-//! The [`multihash-codetable`] crate defines a set of hashes to get started
-//! quickly. To make your own codetable, use the [`multihash-derive`] crate.
+//! Data can be verified against a multihash with [`Multihash::verify_with`],
+//! or with [`Multihash::verify`] if one of the hash features is enabled.
 //!
 //! The `arb` feature flag enables the quickcheck arbitrary implementation for
 //! property based testing.
@@ -18,13 +21,31 @@
 //!
 //! [Serde]: https://serde.rs
 //! [SCALE Codec]: https://github.com/paritytech/parity-scale-codec
-//! [`multihash-derive`]: https://docs.rs/multihash-derive
-//! [`multihash-codetable`]: https://docs.rs/multihash-codetable
 
+mod borrowed;
+mod builder;
 mod error;
+pub mod hasher;
+#[doc(hidden)]
+pub mod literal;
 mod serde;
-
-pub use error::Error;
+mod table;
+mod verify;
+
+pub use {
+	borrowed::MultihashRef,
+	builder::{MultihashBuilder, DEFAULT_BUFFER_SIZE},
+	error::Error,
+	hasher::Hasher,
+	table::{
+		BuiltinCodeTable,
+		BuiltinHasher,
+		CodeTable,
+		WithCode,
+		WithCodeHasher,
+	},
+	verify::{VerifyError, MIN_DIGEST_LEN},
+};
 
 /// Deprecated type-alias for the [`Multihash`] type.
 #[deprecated(since = "0.18.0", note = "Use `multihash::Multihash instead.")]
@@ -142,6 +163,40 @@ impl<const S: usize> Multihash<S> {
 		Ok(result)
 	}
 
+	/// Parses a multihash from bytes, in a const context.
+	///
+	/// Unlike [`Multihash::from_bytes`], the error doesn't need to be dropped,
+	/// which isn't possible at compile time.
+	pub(crate) const fn from_bytes_const(
+		bytes: &[u8],
+	) -> core::result::Result<Self, &'static str> {
+		let (code, bytes) = match varint::decode::u64_const(bytes) {
+			Ok(decoded) => decoded,
+			Err(_) => return Err("invalid multihash code"),
+		};
+		let (size, digest) = match varint::decode::u64_const(bytes) {
+			Ok(decoded) => decoded,
+			Err(_) => return Err("invalid multihash size"),
+		};
+		if size as usize != digest.len() || size > u8::MAX as u64 {
+			return Err("multihash size doesn't match the digest");
+		}
+		if digest.len() > S {
+			return Err("multihash digest is larger than the allocated size");
+		}
+		let mut mh = Self {
+			code,
+			size: size as u8,
+			digest: [0; S],
+		};
+		let mut i = 0;
+		while i < digest.len() {
+			mh.digest[i] = digest[i];
+			i += 1;
+		}
+		Ok(mh)
+	}
+
 	/// Writes a multihash to a byte stream, returning the written size.
 	pub fn write<W: io::Write>(&self, w: W) -> Result<usize, Error> {
 		write_multihash(w, self.code(), self.size(), self.digest())
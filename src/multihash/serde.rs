@@ -1,7 +1,11 @@
 //! Multihash Serde (de)serialization
+//!
+//! Human readable formats (e.g. `serde_json`) get the multihash as multibase
+//! encoded string, deserializing accepts the bytes as well.
 
 use {
 	super::Multihash,
+	crate::multibase::{self, Base},
 	core::{fmt, mem, slice},
 	serde::{
 		de::{self, SeqAccess, Visitor},
@@ -63,7 +67,12 @@ impl<const SIZE: usize> Serialize for Multihash<SIZE> {
 		let bytes_written = self
 			.write(buffer.as_mut_slice())
 			.map_err(|_| ser::Error::custom("Failed to serialize Multihash"))?;
-		serializer.serialize_bytes(&buffer.as_slice()[..bytes_written])
+		let bytes = &buffer.as_slice()[..bytes_written];
+		if serializer.is_human_readable() {
+			serializer.serialize_str(&multibase::encode(Base::Base58Btc, bytes))
+		} else {
+			serializer.serialize_bytes(bytes)
+		}
 	}
 }
 
@@ -73,7 +82,16 @@ impl<'de, const SIZE: usize> Visitor<'de> for BytesVisitor<SIZE> {
 	type Value = Multihash<SIZE>;
 
 	fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-		write!(fmt, "a valid Multihash in bytes")
+		write!(fmt, "a valid Multihash in bytes or as multibase string")
+	}
+
+	fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+	where
+		E: de::Error,
+	{
+		let (_, bytes) = multibase::decode(value)
+			.map_err(|_| de::Error::custom("Failed to deserialize Multihash"))?;
+		self.visit_bytes(&bytes)
 	}
 
 	fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Self::Value, E>
@@ -113,7 +131,11 @@ impl<'de, const SIZE: usize> Deserialize<'de> for Multihash<SIZE> {
 	where
 		D: Deserializer<'de>,
 	{
-		deserializer.deserialize_bytes(BytesVisitor)
+		if deserializer.is_human_readable() {
+			deserializer.deserialize_any(BytesVisitor)
+		} else {
+			deserializer.deserialize_bytes(BytesVisitor)
+		}
 	}
 }
 
@@ -121,9 +143,9 @@ impl<'de, const SIZE: usize> Deserialize<'de> for Multihash<SIZE> {
 mod tests {
 	use {
 		super::*,
-		alloc::format,
+		alloc::{boxed::Box, format},
 		core::ptr,
-		serde_test::{assert_tokens, Token},
+		serde_test::{assert_tokens, Configure, Token},
 	};
 
 	const SHA2_256_CODE: u64 = 0x12;
@@ -135,20 +157,32 @@ mod tests {
 
 	#[test]
 	fn test_serde_json() {
+		let mh = Multihash::<32>::wrap(SHA2_256_CODE, &DIGEST).unwrap();
+		let expected_json =
+			format!("\"{}\"", multibase::encode(Base::Base58Btc, mh.to_bytes()));
+
+		let json = serde_json::to_string(&mh).unwrap();
+		assert_eq!(json, expected_json);
+
+		let mh_decoded: Multihash<32> = serde_json::from_str(&json).unwrap();
+		assert_eq!(mh, mh_decoded);
+
 		// This is a concatenation of `SHA2_256_CODE + DIGEST_LENGTH + DIGEST`.
-		let expected_json = format!(
+		let bytes_json = format!(
 			"[{},{},159,228,204,198,222,22,114,79,58,48,199,232,242,84,243,198,71,\
 			 25,134,172,177,248,216,207,142,150,206,42,215,219,231,251]",
 			SHA2_256_CODE as u8,
 			DIGEST.len() as u8
 		);
+		let mh_decoded: Multihash<32> = serde_json::from_str(&bytes_json).unwrap();
+		assert_eq!(mh, mh_decoded);
 
-		let mh = Multihash::<32>::wrap(SHA2_256_CODE, &DIGEST).unwrap();
-
-		let json = serde_json::to_string(&mh).unwrap();
-		assert_eq!(json, expected_json);
-
-		let mh_decoded: Multihash<32> = serde_json::from_str(&json).unwrap();
+		// Any multibase is accepted.
+		let base16_json = format!(
+			"\"{}\"",
+			multibase::encode(Base::Base16Lower, mh.to_bytes())
+		);
+		let mh_decoded: Multihash<32> = serde_json::from_str(&base16_json).unwrap();
 		assert_eq!(mh, mh_decoded);
 	}
 
@@ -195,10 +229,16 @@ mod tests {
 		let mh = Multihash::<32>::wrap(SHA2_256_CODE, &DIGEST).unwrap();
 
 		// As bytes.
-		assert_tokens(&mh, &[Token::Bytes(&ENCODED_MULTIHASH_BYTES)]);
+		assert_tokens(&mh.compact(), &[Token::Bytes(&ENCODED_MULTIHASH_BYTES)]);
+
+		// As string.
+		let string = multibase::encode(Base::Base58Btc, ENCODED_MULTIHASH_BYTES);
+		// Tokens need to be `'static`.
+		let string: &'static str = Box::leak(string.into_boxed_str());
+		assert_tokens(&mh.readable(), &[Token::Str(string)]);
 
 		// As sequence.
-		serde_test::assert_de_tokens(&mh, &[
+		serde_test::assert_de_tokens(&mh.compact(), &[
 			Token::Seq { len: Some(34) },
 			Token::U8(SHA2_256_CODE as u8),
 			Token::U8(DIGEST.len() as u8),
@@ -0,0 +1,287 @@
+//! Code tables map multihash codes to hash functions.
+
+use {
+	super::{Error, Hasher, Multihash},
+	crate::multicodec::Multicodec,
+};
+
+/// Maps multihash codes to hash functions.
+///
+/// A table that supports several hash functions usually returns an enum that
+/// dispatches to them, see [`BuiltinHasher`]. Further codes can be added to an
+/// existing table with [`CodeTable::with_code`].
+pub trait CodeTable {
+	/// The hasher that is returned for the supported codes.
+	type Hasher: Hasher;
+
+	/// Returns a new hasher for the code, or `None` if the code isn't
+	/// supported.
+	fn hasher(&self, code: u64) -> Option<Self::Hasher>;
+
+	/// Returns the multihash of the input, hashed with the hash function of the
+	/// code.
+	///
+	/// The identity code is always supported, it wraps the input as it is.
+	fn digest<const S: usize>(
+		&self,
+		code: u64,
+		input: &[u8],
+	) -> Result<Multihash<S>, Error> {
+		if code == Multicodec::Identity.code() {
+			return Multihash::wrap(code, input);
+		}
+		let mut hasher = self.hasher(code).ok_or(Error::unknown_code(code))?;
+		hasher.update(input);
+		Multihash::wrap(code, hasher.finalize())
+	}
+
+	/// Returns a table that supports the additional code.
+	///
+	/// The hasher for the code is created with `new_hasher`. It takes
+	/// precedence over a hasher of this table with the same code.
+	fn with_code<F, H>(self, code: u64, new_hasher: F) -> WithCode<Self, F>
+	where
+		Self: Sized,
+		F: Fn() -> H,
+		H: Hasher,
+	{
+		WithCode {
+			table: self,
+			code,
+			new_hasher,
+		}
+	}
+}
+
+impl<T: CodeTable> CodeTable for &T {
+	type Hasher = T::Hasher;
+
+	fn hasher(&self, code: u64) -> Option<Self::Hasher> {
+		(*self).hasher(code)
+	}
+}
+
+/// The table of the hash functions that are built in.
+///
+/// SHA2-256 and SHA2-512 need the `sha2` feature, BLAKE3 needs the `blake3`
+/// feature. BLAKE3 digests have 256 bits, longer ones of up to 512 bits are
+/// verified with its extendable output.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BuiltinCodeTable;
+
+impl CodeTable for BuiltinCodeTable {
+	type Hasher = BuiltinHasher;
+
+	fn hasher(&self, code: u64) -> Option<Self::Hasher> {
+		match Multicodec::from_code(code)? {
+			#[cfg(feature = "sha2")]
+			Multicodec::Sha2_256 => Some(BuiltinHasher::Sha2_256(Default::default())),
+			#[cfg(feature = "sha2")]
+			Multicodec::Sha2_512 => Some(BuiltinHasher::Sha2_512(Default::default())),
+			#[cfg(feature = "blake3")]
+			Multicodec::Blake3 => Some(BuiltinHasher::Blake3(Default::default())),
+			_ => None,
+		}
+	}
+}
+
+/// The hasher of the [`BuiltinCodeTable`].
+// The hashers are kept inline, so that hashing doesn't allocate.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum BuiltinHasher {
+	/// SHA2-256.
+	#[cfg(feature = "sha2")]
+	Sha2_256(super::hasher::Sha2_256),
+	/// SHA2-512.
+	#[cfg(feature = "sha2")]
+	Sha2_512(super::hasher::Sha2_512),
+	/// BLAKE3, [`Hasher::finalize`] returns 256 bits.
+	#[cfg(feature = "blake3")]
+	Blake3(super::hasher::Blake3<64>),
+}
+
+impl Hasher for BuiltinHasher {
+	#[allow(unused_variables)]
+	fn update(&mut self, input: &[u8]) {
+		match *self {
+			#[cfg(feature = "sha2")]
+			Self::Sha2_256(ref mut hasher) => hasher.update(input),
+			#[cfg(feature = "sha2")]
+			Self::Sha2_512(ref mut hasher) => hasher.update(input),
+			#[cfg(feature = "blake3")]
+			Self::Blake3(ref mut hasher) => hasher.update(input),
+		}
+	}
+
+	fn finalize(&mut self) -> &[u8] {
+		match *self {
+			#[cfg(feature = "sha2")]
+			Self::Sha2_256(ref mut hasher) => hasher.finalize(),
+			#[cfg(feature = "sha2")]
+			Self::Sha2_512(ref mut hasher) => hasher.finalize(),
+			#[cfg(feature = "blake3")]
+			Self::Blake3(ref mut hasher) => &hasher.finalize()[..32],
+		}
+	}
+
+	#[allow(unused_variables)]
+	fn finalize_len(&mut self, len: usize) -> Option<&[u8]> {
+		match *self {
+			#[cfg(feature = "sha2")]
+			Self::Sha2_256(ref mut hasher) => hasher.finalize_len(len),
+			#[cfg(feature = "sha2")]
+			Self::Sha2_512(ref mut hasher) => hasher.finalize_len(len),
+			#[cfg(feature = "blake3")]
+			Self::Blake3(ref mut hasher) => hasher.finalize_len(len),
+		}
+	}
+
+	fn reset(&mut self) {
+		match *self {
+			#[cfg(feature = "sha2")]
+			Self::Sha2_256(ref mut hasher) => hasher.reset(),
+			#[cfg(feature = "sha2")]
+			Self::Sha2_512(ref mut hasher) => hasher.reset(),
+			#[cfg(feature = "blake3")]
+			Self::Blake3(ref mut hasher) => hasher.reset(),
+		}
+	}
+}
+
+/// A code table with an additional code, see [`CodeTable::with_code`].
+#[derive(Clone, Debug)]
+pub struct WithCode<T, F> {
+	table: T,
+	code: u64,
+	new_hasher: F,
+}
+
+impl<T, F, H> CodeTable for WithCode<T, F>
+where
+	T: CodeTable,
+	F: Fn() -> H,
+	H: Hasher,
+{
+	type Hasher = WithCodeHasher<T::Hasher, H>;
+
+	fn hasher(&self, code: u64) -> Option<Self::Hasher> {
+		if code == self.code {
+			Some(WithCodeHasher::Code((self.new_hasher)()))
+		} else {
+			self.table.hasher(code).map(WithCodeHasher::Table)
+		}
+	}
+}
+
+/// The hasher of [`WithCode`], it's either the one of the underlying table or
+/// the one of the additional code.
+#[derive(Clone, Debug)]
+pub enum WithCodeHasher<A, B> {
+	/// The hasher of the underlying table.
+	Table(A),
+	/// The hasher of the additional code.
+	Code(B),
+}
+
+impl<A: Hasher, B: Hasher> Hasher for WithCodeHasher<A, B> {
+	fn update(&mut self, input: &[u8]) {
+		match self {
+			Self::Table(hasher) => hasher.update(input),
+			Self::Code(hasher) => hasher.update(input),
+		}
+	}
+
+	fn finalize(&mut self) -> &[u8] {
+		match self {
+			Self::Table(hasher) => hasher.finalize(),
+			Self::Code(hasher) => hasher.finalize(),
+		}
+	}
+
+	fn finalize_len(&mut self, len: usize) -> Option<&[u8]> {
+		match self {
+			Self::Table(hasher) => hasher.finalize_len(len),
+			Self::Code(hasher) => hasher.finalize_len(len),
+		}
+	}
+
+	fn reset(&mut self) {
+		match self {
+			Self::Table(hasher) => hasher.reset(),
+			Self::Code(hasher) => hasher.reset(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// A domain separated hasher, it only sums up the bytes.
+	#[derive(Default)]
+	struct SumHasher {
+		sum: [u8; 2],
+	}
+
+	impl Hasher for SumHasher {
+		fn update(&mut self, input: &[u8]) {
+			let sum = input
+				.iter()
+				.fold(u16::from_be_bytes(self.sum), |sum, byte| {
+					sum.wrapping_add(u16::from(*byte))
+				});
+			self.sum = sum.to_be_bytes();
+		}
+
+		fn finalize(&mut self) -> &[u8] {
+			&self.sum
+		}
+
+		fn reset(&mut self) {
+			self.sum = [0; 2];
+		}
+	}
+
+	const SUM_CODE: u64 = 0x300000;
+
+	#[test]
+	fn test_with_code() {
+		let table = BuiltinCodeTable.with_code(SUM_CODE, SumHasher::default);
+		let multihash: Multihash<64> = table.digest(SUM_CODE, &[1, 2, 3]).unwrap();
+		assert_eq!(multihash.code(), SUM_CODE);
+		assert_eq!(multihash.digest(), [0, 6]);
+		assert!(BuiltinCodeTable.digest::<64>(SUM_CODE, &[1]).is_err());
+		assert!(table.digest::<64>(SUM_CODE + 1, &[1]).is_err());
+	}
+
+	#[test]
+	fn test_identity() {
+		let multihash: Multihash<64> =
+			BuiltinCodeTable.digest(0x00, b"hello").unwrap();
+		assert_eq!(multihash.digest(), b"hello");
+		assert!(BuiltinCodeTable.digest::<4>(0x00, b"hello").is_err());
+	}
+
+	#[cfg(feature = "sha2")]
+	#[test]
+	fn test_builtin() {
+		use sha2::Digest;
+
+		let table = BuiltinCodeTable.with_code(SUM_CODE, SumHasher::default);
+		let multihash: Multihash<64> = table.digest(0x12, b"hello").unwrap();
+		assert_eq!(multihash.digest(), &sha2::Sha256::digest(b"hello")[..]);
+		let multihash: Multihash<64> = table.digest(0x13, b"hello").unwrap();
+		assert_eq!(multihash.digest(), &sha2::Sha512::digest(b"hello")[..]);
+		assert!(table.digest::<32>(0x13, b"hello").is_err());
+	}
+
+	#[cfg(feature = "blake3")]
+	#[test]
+	fn test_builtin_blake3() {
+		let multihash: Multihash<64> =
+			BuiltinCodeTable.digest(0x1e, b"hello").unwrap();
+		assert_eq!(multihash.digest(), blake3::hash(b"hello").as_bytes());
+	}
+}
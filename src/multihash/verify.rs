@@ -0,0 +1,251 @@
+use {
+	super::{CodeTable, Hasher, Multihash, MultihashRef},
+	crate::multicodec::{CodeName, Multicodec},
+	core::fmt,
+	core2::error::Error as StdError,
+};
+
+/// Error returned when data is verified against a multihash.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerifyError {
+	/// There is no hash function for the code of the multihash.
+	UnknownCode(u64),
+	/// The digest of the data doesn't match the digest of the multihash.
+	DigestMismatch,
+	/// The digest of the multihash is truncated too much to be checked, see
+	/// [`MIN_DIGEST_LEN`].
+	DigestTooShort,
+}
+
+/// The minimum length of a truncated digest that is verified.
+///
+/// Shorter digests, down to an empty one, would match data that is easy to
+/// find. Hash functions with a shorter output need the whole digest.
+pub const MIN_DIGEST_LEN: usize = 16;
+
+impl fmt::Display for VerifyError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::UnknownCode(code) => {
+				write!(f, "Unknown multihash code {:?}.", CodeName(*code))
+			}
+			Self::DigestMismatch => write!(f, "Digest doesn't match the data."),
+			Self::DigestTooShort => write!(f, "Digest is truncated too much."),
+		}
+	}
+}
+
+impl StdError for VerifyError {}
+
+impl<const S: usize> Multihash<S> {
+	/// Verifies that the multihash is the digest of the given data.
+	///
+	/// The hash function is picked by the code of the multihash. Truncated
+	/// digests (see [`Multihash::truncate`]) are compared with the prefix of the
+	/// full digest, identity multihashes are compared with the data directly.
+	/// Digests shorter than [`MIN_DIGEST_LEN`] are rejected, unless the hash
+	/// function doesn't produce more.
+	///
+	/// Only the hash functions of the [`BuiltinCodeTable`] are supported, use
+	/// [`Multihash::verify_with`] for others. Needs the `sha2` or `blake3`
+	/// feature.
+	#[cfg(any(feature = "sha2", feature = "blake3"))]
+	pub fn verify(&self, data: &[u8]) -> Result<(), VerifyError> {
+		MultihashRef::from(self).verify(data)
+	}
+
+	/// Verifies that the multihash is the digest of the given data, with the
+	/// hash function from the given table.
+	pub fn verify_with<T: CodeTable>(
+		&self,
+		table: &T,
+		data: &[u8],
+	) -> Result<(), VerifyError> {
+		MultihashRef::from(self).verify_with(table, data)
+	}
+}
+
+impl MultihashRef<'_> {
+	/// Verifies that the multihash is the digest of the given data, see
+	/// [`Multihash::verify`].
+	#[cfg(any(feature = "sha2", feature = "blake3"))]
+	pub fn verify(&self, data: &[u8]) -> Result<(), VerifyError> {
+		self.verify_with(&super::BuiltinCodeTable, data)
+	}
+
+	/// Verifies that the multihash is the digest of the given data, see
+	/// [`Multihash::verify_with`].
+	pub fn verify_with<T: CodeTable>(
+		&self,
+		table: &T,
+		data: &[u8],
+	) -> Result<(), VerifyError> {
+		let digest = self.digest();
+		if self.code() == Multicodec::Identity.code() {
+			return check(digest == data);
+		}
+
+		let mut hasher = table
+			.hasher(self.code())
+			.ok_or(VerifyError::UnknownCode(self.code()))?;
+		hasher.update(data);
+		if digest.len() < hasher.finalize().len().min(MIN_DIGEST_LEN) {
+			return Err(VerifyError::DigestTooShort);
+		}
+		check(hasher.finalize_len(digest.len()) == Some(digest))
+	}
+}
+
+fn check(matches: bool) -> Result<(), VerifyError> {
+	if matches {
+		Ok(())
+	} else {
+		Err(VerifyError::DigestMismatch)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, crate::multihash::BuiltinCodeTable};
+
+	#[test]
+	fn test_verify_identity() {
+		let multihash = Multihash::<64>::wrap(0x00, b"hello").unwrap();
+		assert_eq!(multihash.verify_with(&BuiltinCodeTable, b"hello"), Ok(()));
+		assert_eq!(
+			multihash.verify_with(&BuiltinCodeTable, b"hell"),
+			Err(VerifyError::DigestMismatch)
+		);
+		assert_eq!(
+			multihash
+				.truncate(4)
+				.verify_with(&BuiltinCodeTable, b"hello"),
+			Err(VerifyError::DigestMismatch)
+		);
+	}
+
+	#[test]
+	fn test_verify_unknown_code() {
+		let multihash = Multihash::<64>::wrap(0x300000, &[1; 32]).unwrap();
+		assert_eq!(
+			multihash.verify_with(&BuiltinCodeTable, b"hello"),
+			Err(VerifyError::UnknownCode(0x300000))
+		);
+		assert_eq!(
+			alloc::format!("{}", VerifyError::UnknownCode(0x14)),
+			"Unknown multihash code sha3-512 (0x14)."
+		);
+		assert_eq!(
+			alloc::format!("{}", VerifyError::UnknownCode(0x300000)),
+			"Unknown multihash code 0x300000."
+		);
+	}
+
+	#[test]
+	fn test_verify_with() {
+		/// Hashes everything to the length of the input.
+		#[derive(Default)]
+		struct LenHasher([u8; 1]);
+
+		impl Hasher for LenHasher {
+			fn update(&mut self, input: &[u8]) {
+				self.0[0] = self.0[0].wrapping_add(input.len() as u8);
+			}
+
+			fn finalize(&mut self) -> &[u8] {
+				&self.0
+			}
+
+			fn reset(&mut self) {
+				self.0 = [0];
+			}
+		}
+
+		let table = BuiltinCodeTable.with_code(0x300000, LenHasher::default);
+		let multihash = Multihash::<64>::wrap(0x300000, &[5]).unwrap();
+		assert_eq!(multihash.verify_with(&table, b"hello"), Ok(()));
+		assert_eq!(
+			multihash.verify_with(&table, b"hi"),
+			Err(VerifyError::DigestMismatch)
+		);
+		// The whole digest of a short hash function is needed.
+		let empty = Multihash::<64>::wrap(0x300000, &[]).unwrap();
+		assert_eq!(
+			empty.verify_with(&table, b"hello"),
+			Err(VerifyError::DigestTooShort)
+		);
+		assert_eq!(
+			multihash.verify_with(&BuiltinCodeTable, b"hello"),
+			Err(VerifyError::UnknownCode(0x300000))
+		);
+		assert_eq!(
+			alloc::format!("{}", VerifyError::UnknownCode(0x14)),
+			"Unknown multihash code sha3-512 (0x14)."
+		);
+		assert_eq!(
+			alloc::format!("{}", VerifyError::UnknownCode(0x300000)),
+			"Unknown multihash code 0x300000."
+		);
+	}
+
+	#[cfg(feature = "sha2")]
+	#[test]
+	fn test_verify_sha2() {
+		use sha2::{Digest, Sha256, Sha512};
+
+		let sha2_256 = Sha256::digest(b"hello");
+		let sha2_512 = Sha512::digest(b"hello");
+		for (code, digest) in [(0x12, &sha2_256[..]), (0x13, &sha2_512[..])] {
+			let multihash = Multihash::<64>::wrap(code, digest).unwrap();
+			assert_eq!(multihash.verify(b"hello"), Ok(()));
+			assert_eq!(multihash.truncate(20).verify(b"hello"), Ok(()));
+			assert_eq!(
+				multihash.verify(b"hello!"),
+				Err(VerifyError::DigestMismatch)
+			);
+		}
+
+		// A digest that is longer than the output of the hash function.
+		let mut digest = [0; 40];
+		digest[..32].copy_from_slice(&sha2_256);
+		let multihash = Multihash::<64>::wrap(0x12, &digest).unwrap();
+		assert_eq!(multihash.verify(b"hello"), Err(VerifyError::DigestMismatch));
+
+		// Digests that are truncated too much would match any data.
+		let empty = Multihash::<64>::wrap(0x12, &[]).unwrap();
+		assert_eq!(empty.verify(b"anything"), Err(VerifyError::DigestTooShort));
+		let cid = crate::cid::Cid::new_v1(0x55, empty);
+		assert_eq!(cid.verify(b"anything"), Err(VerifyError::DigestTooShort));
+		let multihash = Multihash::<64>::wrap(0x12, &sha2_256).unwrap();
+		assert_eq!(
+			multihash.truncate(1).verify(b"hello"),
+			Err(VerifyError::DigestTooShort)
+		);
+		assert_eq!(
+			multihash.truncate(MIN_DIGEST_LEN as u8).verify(b"hello"),
+			Ok(())
+		);
+	}
+
+	#[cfg(feature = "blake3")]
+	#[test]
+	fn test_verify_blake3() {
+		let digest = blake3::hash(b"hello");
+		let multihash = Multihash::<64>::wrap(0x1e, digest.as_bytes()).unwrap();
+		assert_eq!(multihash.verify(b"hello"), Ok(()));
+		assert_eq!(multihash.truncate(16).verify(b"hello"), Ok(()));
+		assert_eq!(
+			multihash.verify(b"hello!"),
+			Err(VerifyError::DigestMismatch)
+		);
+
+		// The extendable output is used for longer digests.
+		let mut long = [0; 64];
+		blake3::Hasher::new()
+			.update(b"hello")
+			.finalize_xof()
+			.fill(&mut long);
+		let multihash = Multihash::<64>::wrap(0x1e, &long).unwrap();
+		assert_eq!(multihash.verify(b"hello"), Ok(()));
+	}
+}
@@ -18,16 +18,26 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-#![allow(dead_code)]
+//! Unsigned varints, as specified by
+//! [multiformats](https://github.com/multiformats/unsigned-varint).
+//!
+//! The same encoding is used within CIDs and multihashes. Varints are at most
+//! [`MAX_LEN`] bytes long, so values need to be smaller than 2^63, and they
+//! must be minimal, i.e. must not end with a zero byte. Every value has exactly
+//! one valid encoding.
 
 use {core::fmt, core2::io};
 
+/// The maximum length of a varint in bytes.
+pub const MAX_LEN: usize = 9;
+
 macro_rules! gen {
     ($($name:ident, $d:expr, $t:ident, $b:ident);*) => {
         $(
+            #[doc = concat!("Read a ", $d, " from the reader, one byte at a time.")]
             pub fn $name<R: io::Read>(mut reader: R) -> Result<$t, ReadError> {
                 let mut b = encode::$b();
-                for i in 0 .. b.len() {
+                for i in 0 .. b.len().min(MAX_LEN) {
                     let n = reader.read(&mut b[i .. i + 1])?;
                     if n == 0 {
                         return Err(ReadError::Io(io::ErrorKind::UnexpectedEof.into()))
@@ -51,11 +61,46 @@ gen! {
 		read_usize, "`usize`", usize, usize_buffer
 }
 
+macro_rules! gen_write {
+    ($($name:ident, $d:expr, $t:ident, $b:ident);*) => {
+        $(
+            #[doc = concat!("Write a ", $d, " to the writer, without allocating.")]
+            ///
+            /// Returns the number of bytes written. Fails with
+            /// `io::ErrorKind::InvalidInput` if the value needs more than
+            /// [`MAX_LEN`] bytes.
+            pub fn $name<W: io::Write>(number: $t, mut writer: W) -> io::Result<usize> {
+                let mut b = encode::$b();
+                let bytes = encode::$t(number, &mut b);
+                if bytes.len() > MAX_LEN {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "varint exceeds 9 bytes",
+                    ));
+                }
+                writer.write_all(bytes)?;
+                Ok(bytes.len())
+            }
+        )*
+    }
+}
+
+gen_write! {
+		write_u8,    "`u8`",    u8,    u8_buffer;
+		write_u16,   "`u16`",   u16,   u16_buffer;
+		write_u32,   "`u32`",   u32,   u32_buffer;
+		write_u64,   "`u64`",   u64,   u64_buffer;
+		write_u128,  "`u128`",  u128,  u128_buffer;
+		write_usize, "`usize`", usize, usize_buffer
+}
+
 /// Possible read errors.
 #[non_exhaustive]
 #[derive(Debug)]
 pub enum ReadError {
+	/// Reading failed.
 	Io(io::Error),
+	/// The bytes aren't a valid varint.
 	Decode(decode::Error),
 }
 
@@ -99,19 +144,18 @@ impl From<ReadError> for io::Error {
 	}
 }
 
+/// Decoding of varints from byte slices.
 pub mod decode {
-	use core::fmt;
+	use {super::MAX_LEN, core::fmt};
 
 	/// Possible decoding errors.
-	///
-	/// **Note**: The `std` feature is required for the `std::error::Error` impl
-	/// and the conversion to `std::io::Error`.
 	#[non_exhaustive]
 	#[derive(Clone, Debug, PartialEq, Eq)]
 	pub enum Error {
 		/// Not enough input bytes.
 		Insufficient,
-		/// Input bytes exceed maximum.
+		/// Input bytes exceed maximum, either [`MAX_LEN`] bytes or the size of
+		/// the type.
 		Overflow,
 		/// Encoding is not minimal (has trailing zero bytes).
 		NotMinimal,
@@ -127,10 +171,8 @@ pub mod decode {
 		}
 	}
 
-	/// Only available when the feature `std` is present.
 	impl core2::error::Error for Error {}
 
-	/// Only available when the feature `std` is present.
 	impl From<Error> for core2::io::Error {
 		fn from(val: Error) -> Self {
 			let kind = match val {
@@ -147,7 +189,12 @@ pub mod decode {
 			let mut n = 0;
 			for (i, b) in $buf.iter().cloned().enumerate() {
 				let k = $typ::from(b & 0x7F);
-				n |= k << (i * 7);
+				let shift = i * 7;
+				// Bits that don't fit into the type must not be dropped silently.
+				if shift >= $typ::BITS as usize || (k << shift) >> shift != k {
+					return Err(Error::Overflow);
+				}
+				n |= k << shift;
 				if is_last(b) {
 					if b == 0 && i > 0 {
 						// If last byte (of a multi-byte varint) is zero, it could have been
@@ -156,7 +203,7 @@ pub mod decode {
 					}
 					return Ok((n, &$buf[i + 1..]));
 				}
-				if i == $max_bytes {
+				if i + 1 == $max_bytes {
 					return Err(Error::Overflow);
 				}
 			}
@@ -166,7 +213,7 @@ pub mod decode {
 
 	/// Is this the last byte of an unsigned varint?
 	#[inline]
-	pub fn is_last(b: u8) -> bool {
+	pub const fn is_last(b: u8) -> bool {
 		b & 0x80 == 0
 	}
 
@@ -175,7 +222,7 @@ pub mod decode {
 	/// Returns the value and the remaining slice.
 	#[inline]
 	pub fn u8(buf: &[u8]) -> Result<(u8, &[u8]), Error> {
-		decode!(buf, 1, u8)
+		decode!(buf, 2, u8)
 	}
 
 	/// Decode the given slice as `u16`.
@@ -183,7 +230,7 @@ pub mod decode {
 	/// Returns the value and the remaining slice.
 	#[inline]
 	pub fn u16(buf: &[u8]) -> Result<(u16, &[u8]), Error> {
-		decode!(buf, 2, u16)
+		decode!(buf, 3, u16)
 	}
 
 	/// Decode the given slice as `u32`.
@@ -191,7 +238,7 @@ pub mod decode {
 	/// Returns the value and the remaining slice.
 	#[inline]
 	pub fn u32(buf: &[u8]) -> Result<(u32, &[u8]), Error> {
-		decode!(buf, 4, u32)
+		decode!(buf, 5, u32)
 	}
 
 	/// Decode the given slice as `u64`.
@@ -199,7 +246,31 @@ pub mod decode {
 	/// Returns the value and the remaining slice.
 	#[inline]
 	pub fn u64(buf: &[u8]) -> Result<(u64, &[u8]), Error> {
-		decode!(buf, 9, u64)
+		decode!(buf, MAX_LEN, u64)
+	}
+
+	/// Decode the given slice as `u64`, in a const context.
+	///
+	/// Returns the value and the remaining slice.
+	pub const fn u64_const(buf: &[u8]) -> Result<(u64, &[u8]), Error> {
+		let mut n = 0;
+		let mut i = 0;
+		let mut rest = buf;
+		while let [b, tail @ ..] = rest {
+			rest = tail;
+			n |= ((*b & 0x7F) as u64) << (i * 7);
+			if is_last(*b) {
+				if *b == 0 && i > 0 {
+					return Err(Error::NotMinimal);
+				}
+				return Ok((n, rest));
+			}
+			if i + 1 == MAX_LEN {
+				return Err(Error::Overflow);
+			}
+			i += 1;
+		}
+		Err(Error::Insufficient)
 	}
 
 	/// Decode the given slice as `u128`.
@@ -207,7 +278,7 @@ pub mod decode {
 	/// Returns the value and the remaining slice.
 	#[inline]
 	pub fn u128(buf: &[u8]) -> Result<(u128, &[u8]), Error> {
-		decode!(buf, 18, u128)
+		decode!(buf, MAX_LEN, u128)
 	}
 
 	/// Decode the given slice as `usize`.
@@ -229,8 +300,11 @@ pub mod decode {
 	}
 }
 
+/// Encoding of varints into byte arrays.
+///
+/// The buffers are large enough for any value of the type, values that need
+/// more than [`MAX_LEN`](super::MAX_LEN) bytes aren't valid varints though.
 pub mod encode {
-
 	macro_rules! encode {
 		($number:expr, $buf:expr) => {{
 			let mut n = $number;
@@ -345,15 +419,86 @@ pub mod encode {
 
 	// Required lengths of encoding buffers:
 
-	const U8_LEN: usize = 2;
-	const U16_LEN: usize = 3;
-	const U32_LEN: usize = 5;
-	const U64_LEN: usize = 10;
-	const U128_LEN: usize = 19;
-
+	/// Length of the buffer for `u8` values.
+	pub const U8_LEN: usize = 2;
+	/// Length of the buffer for `u16` values.
+	pub const U16_LEN: usize = 3;
+	/// Length of the buffer for `u32` values.
+	pub const U32_LEN: usize = 5;
+	/// Length of the buffer for `u64` values.
+	pub const U64_LEN: usize = 10;
+	/// Length of the buffer for `u128` values.
+	pub const U128_LEN: usize = 19;
+
+	/// Length of the buffer for `usize` values.
 	#[cfg(target_pointer_width = "64")]
-	const USIZE_LEN: usize = U64_LEN;
+	pub const USIZE_LEN: usize = U64_LEN;
 
+	/// Length of the buffer for `usize` values.
 	#[cfg(target_pointer_width = "32")]
-	const USIZE_LEN: usize = U32_LEN;
+	pub const USIZE_LEN: usize = U32_LEN;
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, alloc::vec::Vec};
+
+	#[test]
+	fn test_roundtrip() {
+		for number in [0, 1, 127, 128, 300, 16_384, u32::MAX as u64, (1 << 63) - 1]
+		{
+			let mut buf = encode::u64_buffer();
+			let bytes = encode::u64(number, &mut buf);
+			assert_eq!(decode::u64(bytes), Ok((number, &[][..])));
+			assert_eq!(decode::u64_const(bytes), Ok((number, &[][..])));
+			assert_eq!(read_u64(bytes).unwrap(), number);
+
+			let mut written = Vec::new();
+			assert_eq!(write_u64(number, &mut written).unwrap(), bytes.len());
+			assert_eq!(written, bytes);
+		}
+		assert_eq!(decode::u16(&[0xac, 0x02, 0xff]), Ok((300, &[0xff][..])));
+	}
+
+	#[test]
+	fn test_max_len() {
+		let mut buf = encode::u64_buffer();
+		let bytes = encode::u64(1 << 63, &mut buf);
+		assert_eq!(bytes.len(), 10);
+		assert_eq!(decode::u64(bytes), Err(decode::Error::Overflow));
+		assert_eq!(decode::u64_const(bytes), Err(decode::Error::Overflow));
+		assert!(read_u64(bytes).is_err());
+		assert_eq!(
+			write_u64(1 << 63, Vec::new()).unwrap_err().kind(),
+			io::ErrorKind::InvalidInput
+		);
+		assert!(write_u128(1 << 63, Vec::new()).is_err());
+
+		// Bits beyond the size of the type aren't dropped.
+		assert_eq!(decode::u8(&[0xff, 0x01]), Ok((255, &[][..])));
+		assert_eq!(decode::u8(&[0xff, 0x02]), Err(decode::Error::Overflow));
+		assert_eq!(
+			decode::u8(&[0x80, 0x80, 0x01]),
+			Err(decode::Error::Overflow)
+		);
+		assert_eq!(
+			decode::u32(&[0xff, 0xff, 0xff, 0xff, 0x1f]),
+			Err(decode::Error::Overflow)
+		);
+	}
+
+	#[test]
+	fn test_not_minimal() {
+		assert_eq!(decode::u64(&[0x00]), Ok((0, &[][..])));
+		assert_eq!(decode::u64(&[0x80, 0x00]), Err(decode::Error::NotMinimal));
+		assert_eq!(
+			decode::u64(&[0x81, 0x80, 0x00]),
+			Err(decode::Error::NotMinimal)
+		);
+		assert_eq!(
+			decode::u64_const(&[0x81, 0x00]),
+			Err(decode::Error::NotMinimal)
+		);
+		assert_eq!(decode::u64(&[0x81]), Err(decode::Error::Insufficient));
+	}
 }